@@ -0,0 +1,1190 @@
+#![allow(unused_imports)]
+// Bug: god + projectile = bug
+pub mod board;
+pub mod components;
+pub mod enemy;
+pub mod event;
+pub mod level;
+pub mod line;
+pub mod player;
+pub mod projectile;
+pub mod replay;
+pub mod save;
+pub mod score;
+pub mod status;
+pub mod turret;
+pub mod units;
+pub mod wave;
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, VecDeque},
+    rc::Rc,
+    vec,
+};
+
+use serde::{Deserialize, Serialize};
+
+use board::{BoardSize, Map};
+use enemy::Enemy;
+use event::Event;
+use level::{standard_unlocks, Goal, Level, Unlock};
+use line::Line;
+use player::Player;
+use projectile::Owner;
+use replay::{Command, InputLog};
+use turret::{Reach, Turret};
+
+use self::wave::{Wave, WaveLine};
+use crate::{
+    utils::{self, Rng, DEFAULT_SEED},
+    FPS,
+};
+
+pub type Reward = u32;
+
+pub const BOARD_LENGHT: f32 = 110.;
+
+pub const GOD_RECHAGE_TIME: u32 = 20 * FPS as u32;
+pub const GOD_LEVEL_MAX: u32 = 7;
+pub const GOD_CHARGED: u32 = GOD_RECHAGE_TIME * (GOD_LEVEL_MAX - 1);
+
+fn get_rng_lines(rng: &mut Rng, lenght: usize, amount: usize) -> Vec<usize> {
+    if lenght == 0 || amount == 0 {
+        return Vec::new();
+    }
+
+    let mut vec = (0..lenght).collect::<Vec<usize>>();
+
+    for i in vec.clone() {
+        let goto = (rng.next_f64() * (lenght - 1) as f64) as usize;
+        vec.swap(i, goto);
+    }
+
+    let drained = vec.drain(0..amount.min(lenght)).collect::<Vec<usize>>();
+    drained
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Game {
+    board: BoardSize,
+    pub lines: Vec<Line>,
+    pub money: u32,
+    pub player: Player,
+    #[serde(skip)]
+    pub action: Option<ActionOnBoard>,
+    pub waves: VecDeque<Wave>,
+    wave_counter: usize,
+    /// The started wave is not cleared yet
+    in_wave: bool,
+    max_wave: usize,
+    pub god: u32,
+    pub stats: GameStats,
+    turret_list: Rc<Vec<Rc<Turret>>>,
+    unlocks: Vec<Unlock>,
+    goal: Goal,
+    kills: u32,
+    /// Money earned by killing enemies
+    earned: u32,
+    rng: Rng,
+    frame: u64,
+    inputs: InputLog,
+    #[serde(skip)]
+    events: Vec<Event>,
+    /// Number of events produced by the last processed frame
+    #[serde(skip)]
+    frame_events: usize,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        let board = BoardSize::default();
+        Self {
+            board,
+            lines: vec![Line::new(board); board.lines],
+            money: if cfg!(debug_assertions) { 99999 } else { 0 },
+            player: Player::default(),
+            action: None,
+            god: 1,
+            waves: VecDeque::new(),
+            wave_counter: 0,
+            in_wave: false,
+            max_wave: 0,
+            stats: GameStats::Playing,
+            turret_list: Rc::new(vec![
+                Rc::new(Turret::prefab_turret(1).unwrap()),
+                Rc::new(Turret::prefab_turret(2).unwrap()),
+                Rc::new(Turret::prefab_turret(3).unwrap()),
+            ]),
+            unlocks: standard_unlocks(),
+            goal: Goal::AllWaves,
+            kills: 0,
+            earned: 0,
+            rng: Rng::new(DEFAULT_SEED),
+            frame: 0,
+            inputs: InputLog::new(DEFAULT_SEED),
+            events: Vec::new(),
+            frame_events: 0,
+        }
+    }
+}
+
+impl Game {
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            inputs: InputLog::new(seed),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_board(seed: u64, board: BoardSize) -> Self {
+        Self {
+            board,
+            lines: vec![Line::new(board); board.lines],
+            ..Self::with_seed(seed)
+        }
+    }
+
+    /// A game on the board of a level, which must have been checked with `Map::check`.
+    pub fn with_map(seed: u64, map: &Map) -> Self {
+        let mut game = Self::with_board(seed, map.size);
+        for (y, line) in game.lines.iter_mut().enumerate() {
+            let (terrain, cells_terrain) = map.line_terrain(y);
+            line.set_terrain(terrain, cells_terrain);
+        }
+        game
+    }
+
+    /// A game of the level, which must have been checked like the ones of `level::CAMPAIGN`.
+    pub fn from_level(seed: u64, level: &Level) -> Self {
+        let mut game = Self::with_map(seed, &level.map);
+        game.money = level.money;
+        game.turret_list = Rc::new(
+            level
+                .turrets
+                .iter()
+                .filter_map(|&level| Turret::prefab_turret(level))
+                .map(Rc::new)
+                .collect(),
+        );
+        game.unlocks = level.unlocks.clone();
+        game.goal = level.goal;
+        game.add_waves(level.waves.clone());
+        game.generate_waves(level.generated);
+        game.assign_line_for_enemies();
+        game
+    }
+
+    /// The prefab waves followed by five generated ones.
+    #[inline]
+    pub fn standard(seed: u64) -> Self {
+        Self::from_level(seed, &Level::standard())
+    }
+
+    #[inline]
+    pub fn endless(seed: u64) -> Self {
+        Self::from_level(seed, &Level::endless())
+    }
+
+    /// The endless game of the daily challenge of `date`, the same for every player.
+    #[inline]
+    pub fn daily(date: &str) -> Self {
+        Self::endless(utils::seed_from_date(date))
+    }
+
+    #[inline]
+    pub fn board(&self) -> BoardSize {
+        self.board
+    }
+
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.rng.seed()
+    }
+
+    /// Number of frames processed since the beginning of the game
+    #[inline]
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    #[inline]
+    pub fn input_log(&self) -> &InputLog {
+        &self.inputs
+    }
+
+    /// The events of the last processed frame, followed by the ones of the commands applied since.
+    #[inline]
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    #[inline]
+    fn record(&mut self, command: Command) {
+        self.inputs.push(self.frame, command)
+    }
+
+    pub fn apply(&mut self, command: Command) -> bool {
+        match command {
+            Command::MovePlayerUp => self.move_player_up(),
+            Command::MovePlayerDown => self.move_player_down(),
+            Command::PlayerShoot => self.player_shoot(),
+            Command::PlaceTurret { level, x, y } => return self.place_turret(level, x, y),
+            Command::DeleteTurret { x, y } => return self.delete_turret(x, y),
+            Command::UpgradeTurret { x, y } => return self.upgrade_turret(x, y),
+            Command::RepairTurret { x, y } => return self.repair_turret(x, y),
+            Command::UpgradePlayer => self.upgrade_player(),
+            Command::UseGod => return self.use_god(),
+            Command::StartNextWave => self.start_next_wave(),
+            Command::SkipWave => self.skip_one_wave(),
+            Command::MoreWaves(amount) => self.more_waves(amount),
+        }
+        true
+    }
+
+    pub fn skip_one_wave(&mut self) {
+        self.record(Command::SkipWave);
+        self.prepare_endless_wave();
+        if self.is_wave_ended() && !self.is_no_more_wave() {
+            self.wave_counter += 1;
+            self.unlock_turrets();
+
+            self.money += self
+                .lines
+                .iter_mut()
+                .map(|line| line.skip_one_wave())
+                .sum::<u32>();
+        }
+    }
+
+    #[inline]
+    pub fn turret_list(&self) -> Rc<Vec<Rc<Turret>>> {
+        self.turret_list.clone()
+    }
+
+    #[inline]
+    pub fn move_player_up(&mut self) {
+        self.record(Command::MovePlayerUp);
+        self.player.up()
+    }
+
+    #[inline]
+    pub fn move_player_down(&mut self) {
+        self.record(Command::MovePlayerDown);
+        self.player.down(self.board.lines)
+    }
+
+    #[inline]
+    pub fn add_wave(&mut self, wave: Wave) {
+        self.max_wave += 1;
+        self.waves.push_back(wave)
+    }
+
+    #[inline]
+    pub fn add_waves(&mut self, waves: Vec<Wave>) {
+        for wave in waves {
+            self.add_wave(wave)
+        }
+    }
+
+    #[inline]
+    pub fn generate_wave(&mut self) {
+        let wave = Wave::generate(&mut self.rng, self.max_wave as u32 + 1, self.board.lines);
+        self.add_wave(wave)
+    }
+
+    #[inline]
+    pub fn generate_waves(&mut self, amount: u32) {
+        for _ in 0..amount {
+            self.generate_wave()
+        }
+    }
+
+    /// Keep playing after a victory.
+    pub fn more_waves(&mut self, amount: u32) {
+        self.record(Command::MoreWaves(amount));
+        self.generate_waves(amount);
+        self.assign_line_for_enemies();
+        self.stats = GameStats::Playing;
+    }
+
+    #[inline]
+    pub fn is_delete_mode(&self) -> bool {
+        matches!(self.action, Some(ActionOnBoard::Delete))
+    }
+
+    #[inline]
+    pub fn is_upgrade_mode(&self) -> bool {
+        matches!(self.action, Some(ActionOnBoard::Upgrade))
+    }
+
+    #[inline]
+    pub fn is_repair_mode(&self) -> bool {
+        matches!(self.action, Some(ActionOnBoard::Repair))
+    }
+
+    #[inline]
+    pub fn wave(&self) -> usize {
+        self.wave_counter
+    }
+
+    /// Number of waves of the game, the last one is `wave_count()`.
+    #[inline]
+    pub fn wave_count(&self) -> usize {
+        self.max_wave
+    }
+
+    #[inline]
+    pub fn start_next_wave(&mut self) {
+        self.record(Command::StartNextWave);
+        self.prepare_endless_wave();
+        if self.is_wave_ended() && !self.is_no_more_wave() {
+            self.wave_counter += 1;
+            self.in_wave = true;
+            self.unlock_turrets();
+
+            self.lines.iter_mut().for_each(|line| {
+                line.start_next_wave();
+            });
+            self.events.push(Event::WaveStarted {
+                wave: self.wave_counter,
+            });
+        }
+    }
+
+    /// In endless mode, generate the next wave when none is left.
+    fn prepare_endless_wave(&mut self) {
+        if self.goal == Goal::Endless && self.wave_counter >= self.max_wave && self.is_wave_ended()
+        {
+            self.generate_wave();
+            self.assign_line_for_enemies();
+        }
+    }
+
+    /// Add to the shop the turrets unlocked by the current wave.
+    fn unlock_turrets(&mut self) {
+        let wave = self.wave_counter;
+        let levels = self
+            .unlocks
+            .iter()
+            .filter(|unlock| unlock.wave == wave)
+            .flat_map(|unlock| unlock.turrets.iter().copied())
+            .collect::<Vec<u8>>();
+        if levels.is_empty() {
+            return;
+        }
+
+        let mut turret_list = self.turret_list.as_ref().clone();
+        for level in levels {
+            if !turret_list.iter().any(|t| t.level() == level) {
+                if let Some(turret) = Turret::prefab_turret(level) {
+                    turret_list.push(Rc::new(turret));
+                }
+            }
+        }
+        self.turret_list = Rc::new(turret_list);
+    }
+
+    #[inline]
+    pub fn goal(&self) -> Goal {
+        self.goal
+    }
+
+    fn is_goal_reached(&self) -> bool {
+        match self.goal {
+            Goal::AllWaves => !self.is_remaining_enemies() && self.is_no_more_wave(),
+            Goal::Waves(amount) => {
+                self.wave_counter >= amount && self.is_wave_ended() && !self.is_remaining_enemies()
+            }
+            Goal::Money(amount) => self.money >= amount,
+            Goal::Endless => false,
+        }
+    }
+
+    #[inline]
+    pub fn kills(&self) -> u32 {
+        self.kills
+    }
+
+    /// Number of waves whose every enemy has been killed.
+    pub fn waves_cleared(&self) -> usize {
+        let wave = self.wave_counter.min(self.max_wave);
+        if self.is_wave_ended() && !self.is_remaining_enemies() {
+            wave
+        } else {
+            wave.saturating_sub(1)
+        }
+    }
+
+    #[inline]
+    pub fn score(&self) -> u32 {
+        score::score(self.waves_cleared(), self.kills, self.earned)
+    }
+
+    #[inline]
+    fn is_wave_running(&self) -> bool {
+        self.lines.iter().any(|l| !l.is_wave_ended())
+    }
+
+    #[inline]
+    pub fn is_wave_ended(&self) -> bool {
+        self.lines.iter().all(|line| line.is_wave_ended())
+    }
+
+    #[inline]
+    pub fn is_remaining_enemies(&self) -> bool {
+        self.lines.iter().any(|line| line.is_remaining_enemies())
+    }
+
+    /// The first boss on the board, for its health bar.
+    pub fn boss(&self) -> Option<Enemy> {
+        self.lines
+            .iter()
+            .find_map(|line| line.enemies.borrow().iter().find(|e| e.is_boss()).cloned())
+    }
+
+    #[inline]
+    pub fn is_no_more_wave(&self) -> bool {
+        self.wave_counter > self.max_wave
+    }
+
+    #[inline]
+    pub fn have_action(&self) -> bool {
+        self.action.is_some()
+    }
+
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        matches!(self.stats, GameStats::Pause(_))
+    }
+
+    pub fn assign_line_for_enemies(&mut self) {
+        let mut wave_packs = vec![VecDeque::new(); self.board.lines];
+
+        for mut wave in self.waves.drain(..) {
+            let mut wave_lines = (0..self.board.lines)
+                .map(|_| WaveLine::default())
+                .collect::<Vec<WaveLine>>();
+            let mut frames = wave
+                .troops
+                .keys()
+                .chain(wave.fixed.keys())
+                .cloned()
+                .collect::<BTreeSet<u64>>();
+            let boss_frame = wave.boss_frame();
+            let mut delayed = Vec::new();
+
+            while let Some(frame) = frames.pop_first() {
+                let mut free_lines = (0..self.lines.len()).collect::<Vec<usize>>();
+
+                for &(level, line) in wave.fixed.get(&frame).into_iter().flatten() {
+                    if let Some(wave_line) = wave_lines.get_mut(line) {
+                        wave_line.add_enemy(frame, level);
+                        free_lines.retain(|free| *free != line);
+                    }
+                }
+
+                let mut levels = wave.troops.remove(&frame).unwrap_or_default();
+                levels.append(&mut delayed);
+                for line in get_rng_lines(&mut self.rng, free_lines.len(), levels.len()) {
+                    let wave_line = wave_lines.get_mut(free_lines[line]).unwrap();
+                    wave_line.add_enemy(frame, levels.pop().unwrap());
+                }
+                // The enemies without a free lane come at the next frame
+                if !levels.is_empty() {
+                    delayed = levels;
+                    frames.insert(frame + 1);
+                }
+            }
+
+            if let Some(level) = wave.boss {
+                // The boss comes last, in the middle lane
+                wave_lines[self.board.lines / 2].add_enemy(boss_frame, level);
+            }
+
+            for (i, wave_line) in wave_lines.into_iter().enumerate() {
+                wave_packs[i].push_back(wave_line)
+            }
+        }
+
+        self.lines
+            .iter_mut()
+            .zip(wave_packs)
+            .for_each(|(line, wave)| line.add_waves(wave));
+    }
+
+    pub fn can_execut_action(&self, action: &ActionOnBoard) -> bool {
+        match action {
+            ActionOnBoard::PlaceTurret(ref turret) => self.money >= turret.price(),
+            ActionOnBoard::Delete | ActionOnBoard::Upgrade | ActionOnBoard::Repair => true,
+        }
+    }
+
+    pub fn execute_action(&mut self, x: usize, y: usize) -> bool {
+        match self.action.take() {
+            Some(ActionOnBoard::PlaceTurret(turret)) => self.place_turret(turret.level(), x, y),
+            Some(ActionOnBoard::Delete) => self.delete_turret(x, y),
+            Some(ActionOnBoard::Upgrade) => self.upgrade_turret(x, y),
+            Some(ActionOnBoard::Repair) => self.repair_turret(x, y),
+            None => false,
+        }
+    }
+
+    pub fn place_turret(&mut self, level: u8, x: usize, y: usize) -> bool {
+        self.record(Command::PlaceTurret { level, x, y });
+        if self.board.contains(x, y) && self.lines[y].is_buildable(x) {
+            if let Some(turret) = self.turret_list.iter().find(|t| t.level() == level) {
+                if self.money >= turret.price() {
+                    let turret = turret.as_ref().clone();
+                    self.money -= self.lines[y].add_turret(x, turret);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    pub fn delete_turret(&mut self, x: usize, y: usize) -> bool {
+        self.record(Command::DeleteTurret { x, y });
+        if self.board.contains(x, y) {
+            self.money += self.lines[y].delete_turret(x);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The price of the upgrade of the turret at `(x, y)`, if its upgrade is unlocked.
+    pub fn upgrade_cost(&self, x: usize, y: usize) -> Option<u32> {
+        if !(self.board.contains(x, y)) {
+            return None;
+        }
+        let turret = self.lines[y].cells[x].as_ref()?;
+        let upgraded = turret.upgraded()?;
+        if self
+            .turret_list
+            .iter()
+            .any(|t| t.level() == upgraded.level())
+        {
+            Some(upgraded.price().saturating_sub(turret.price()))
+        } else {
+            None
+        }
+    }
+
+    pub fn upgrade_turret(&mut self, x: usize, y: usize) -> bool {
+        self.record(Command::UpgradeTurret { x, y });
+        match self.upgrade_cost(x, y) {
+            Some(cost) if self.money >= cost => {
+                self.money -= self.lines[y].upgrade_turret(x);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The price of the repair of the turret at `(x, y)`, if it is damaged.
+    pub fn repair_cost(&self, x: usize, y: usize) -> Option<u32> {
+        if !(self.board.contains(x, y)) {
+            return None;
+        }
+        let turret = self.lines[y].cells[x].as_ref()?;
+        if turret.life() < turret.max_life() {
+            Some(turret.repair_cost())
+        } else {
+            None
+        }
+    }
+
+    pub fn repair_turret(&mut self, x: usize, y: usize) -> bool {
+        self.record(Command::RepairTurret { x, y });
+        match self.repair_cost(x, y) {
+            Some(cost) if self.money >= cost => {
+                self.money -= self.lines[y].repair_turret(x);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn upgrade_player(&mut self) {
+        self.record(Command::UpgradePlayer);
+        let upgrade_cost = self.player.upgrade_cost();
+        if self.money >= upgrade_cost && self.player.upgrade() {
+            self.money -= upgrade_cost;
+        }
+    }
+
+    pub fn player_shoot(&mut self) {
+        self.record(Command::PlayerShoot);
+        if self.player.can_attack() {
+            let projectile = self.player.shoot().unwrap();
+            self.events.push(Event::ProjectileFired {
+                line: self.player.line,
+                level: projectile.level(),
+                owner: Owner::Player,
+            });
+            self.lines[self.player.line].spawn_projectile(projectile);
+        }
+    }
+
+    pub fn pause(&mut self, toggle: bool) {
+        if let GameStats::Pause(ref game_stats) = self.stats {
+            self.stats = game_stats.as_ref().clone();
+        } else if toggle {
+            self.stats = GameStats::Pause(Box::new(self.stats.clone()));
+        }
+    }
+
+    pub fn process(&mut self) {
+        self.events.drain(..self.frame_events);
+
+        if matches!(self.stats, GameStats::Playing) {
+            self.frame += 1;
+            if self.god < GOD_CHARGED && self.is_wave_running() {
+                self.god += 1;
+            }
+            // PLAYER WAIT
+            self.player.wait();
+
+            let start = self.events.len();
+            for (y, line) in self.lines.iter_mut().enumerate() {
+                line.process(y, &mut self.events);
+            }
+            self.process_cross_lane();
+            self.switch_lanes();
+            if self.in_wave {
+                for (y, line) in self.lines.iter_mut().enumerate() {
+                    line.produce(y, &mut self.events);
+                }
+            }
+
+            let mut defeat = false;
+            for event in self.events[start..].iter() {
+                match event {
+                    Event::EnemyKilled { reward, .. } => {
+                        self.money += reward;
+                        self.kills += 1;
+                        self.earned += reward;
+                    }
+                    Event::MoneyProduced { money, .. } => self.money += money,
+                    Event::LaneBreached { .. } => defeat = true,
+                    _ => (),
+                }
+            }
+
+            self.check_wave_cleared();
+
+            if self.is_goal_reached() {
+                self.stats = GameStats::Victory
+            } else if defeat
+                // Every wave is over but the goal is not reached
+                || (!self.is_remaining_enemies() && self.is_no_more_wave())
+            {
+                self.stats = GameStats::Defeat
+            }
+        }
+
+        self.frame_events = self.events.len();
+    }
+
+    /// The turrets reaching other lines shoot once every line is processed.
+    fn process_cross_lane(&mut self) {
+        for y in 0..self.lines.len() {
+            for x in 0..self.lines[y].cells.len() {
+                let (reach, hitbox) = match &self.lines[y].cells[x] {
+                    Some(turret) if turret.can_attack() => match turret.reach() {
+                        Some(reach) => (reach, turret.hitbox()),
+                        None => continue,
+                    },
+                    _ => continue,
+                };
+                match reach {
+                    Reach::Mortar => {
+                        let target = [y.checked_sub(1), Some(y + 1)]
+                            .iter()
+                            .flatten()
+                            .copied()
+                            .filter(|&to| to < self.lines.len())
+                            .filter_map(|to| {
+                                Some((to, self.lines[to].most_advanced(hitbox.start())?))
+                            })
+                            .min_by(|(_, x1), (_, x2)| x1.partial_cmp(x2).unwrap());
+                        if let Some((to, _)) = target {
+                            if let Some(shell) = self.lines[y].fire(x, y, &mut self.events) {
+                                self.lines[to].spawn_projectile(shell);
+                            }
+                        }
+                    }
+                    Reach::Column => {
+                        let column = self.board.cell_bounds(x);
+                        if self.lines.iter().any(|line| line.is_enemy_in(column)) {
+                            if let Some(beam) = self.lines[y].fire(x, y, &mut self.events) {
+                                self.events.push(Event::BeamFired {
+                                    line: y,
+                                    x,
+                                    level: beam.level(),
+                                });
+                                for (to, line) in self.lines.iter_mut().enumerate() {
+                                    line.strike(column, &beam, to, &mut self.events);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Move the enemies leaving their line to the least defended adjacent line.
+    fn switch_lanes(&mut self) {
+        for from in 0..self.lines.len() {
+            for mut enemy in self.lines[from].take_leaving() {
+                let x = enemy.hitbox().start();
+                let to = [from.checked_sub(1), Some(from + 1)]
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .filter(|&to| to < self.lines.len())
+                    .min_by_key(|&to| self.lines[to].defense_before(x));
+                match to {
+                    Some(to) => {
+                        enemy.switch_lane(from as f32 - to as f32);
+                        self.events.push(Event::EnemySwitchedLane {
+                            from,
+                            to,
+                            level: enemy.level(),
+                        });
+                        self.lines[to].receive(enemy);
+                    }
+                    // A single line, the enemy stays and waits before trying again
+                    None => {
+                        enemy.switch_lane(0.);
+                        self.lines[from].receive(enemy);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn use_god(&mut self) -> bool {
+        self.record(Command::UseGod);
+        if self.god == GOD_CHARGED {
+            let mut reward = 0;
+            let start = self.events.len();
+            for (y, line) in self.lines.iter_mut().enumerate() {
+                reward += line.use_god(y, &mut self.events);
+            }
+            self.kills += self.events[start..]
+                .iter()
+                .filter(|event| matches!(event, Event::EnemyKilled { .. }))
+                .count() as u32;
+            self.money += reward;
+            self.earned += reward;
+            self.god = 0;
+            self.events.push(Event::GodUsed { reward });
+            self.check_wave_cleared();
+            reward != 0
+        } else {
+            false
+        }
+    }
+
+    /// Tell once the started wave has no enemy left.
+    fn check_wave_cleared(&mut self) {
+        if self.in_wave && !self.is_wave_running() && !self.is_remaining_enemies() {
+            self.in_wave = false;
+            self.events.push(Event::WaveCleared {
+                wave: self.wave_counter,
+            });
+        }
+    }
+
+    #[inline]
+    pub fn god_level(&self) -> u32 {
+        self.god / GOD_RECHAGE_TIME + 1
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ActionOnBoard {
+    PlaceTurret(Turret),
+    Delete,
+    Upgrade,
+    Repair,
+}
+
+impl ActionOnBoard {
+    #[inline]
+    pub fn get_turret_level(&self) -> Option<u8> {
+        match self {
+            Self::PlaceTurret(t) => Some(t.level()),
+            Self::Delete | Self::Upgrade | Self::Repair => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GameStats {
+    Playing,
+    Victory,
+    Defeat,
+    Pause(Box<GameStats>),
+}
+
+#[test]
+fn same_seed_same_game() {
+    fn play(seed: u64) -> (Game, Vec<VecDeque<WaveLine>>) {
+        let mut game = Game::standard(seed);
+        let lanes = game
+            .lines
+            .iter()
+            .map(|line| line.waves.borrow().clone())
+            .collect();
+
+        for _ in 0..15 {
+            game.start_next_wave();
+            for _ in 0..200 * FPS {
+                game.process();
+            }
+        }
+        (game, lanes)
+    }
+
+    let (game1, lanes1) = play(42);
+    let (game2, lanes2) = play(42);
+    assert_eq!(game1.seed(), 42);
+    assert_eq!(lanes1, lanes2);
+    assert_eq!(game1.money, game2.money);
+    assert_eq!(game1.wave(), game2.wave());
+    assert_eq!(game1.stats, game2.stats);
+
+    let (_, lanes3) = play(43);
+    assert_ne!(lanes1, lanes3);
+}
+
+#[test]
+fn upgrade_a_turret() {
+    let mut game = Game::with_seed(1);
+    game.money = 1000;
+    game.place_turret(1, 2, 3);
+    assert_eq!(game.upgrade_cost(2, 3), None);
+    assert!(!game.upgrade_turret(2, 3));
+
+    game.unlocks = vec![Unlock {
+        wave: 1,
+        turrets: vec![4],
+    }];
+    game.add_wave(Wave::default());
+    game.start_next_wave();
+    let x = game.lines[3].cells[2].as_ref().unwrap().hitbox().start();
+    game.lines[3].cells[2].as_mut().unwrap().take_damage(60);
+    assert_eq!(game.upgrade_cost(2, 3), Some(300));
+    assert!(game.upgrade_turret(2, 3));
+    assert_eq!(game.money, 600);
+
+    let turret = game.lines[3].cells[2].as_ref().unwrap();
+    assert_eq!(turret.level(), 4);
+    assert_eq!(turret.life(), turret.max_life() / 2);
+    assert_eq!(turret.hitbox().start(), x);
+    assert_eq!(turret.upgraded().map(|t| t.level()), None);
+}
+
+#[test]
+fn repair_a_turret() {
+    let mut game = Game::with_seed(1);
+    game.money = 200;
+    game.place_turret(1, 0, 0);
+    assert_eq!(game.repair_cost(0, 0), None);
+
+    // A turret of 100 coins with 120 of life, which lost 90 of life
+    game.lines[0].cells[0].as_mut().unwrap().take_damage(90);
+    assert_eq!(game.repair_cost(0, 0), Some(38));
+    assert!(game.repair_turret(0, 0));
+    assert_eq!(game.money, 62);
+
+    let turret = game.lines[0].cells[0].as_ref().unwrap();
+    assert_eq!(turret.life(), turret.max_life());
+    assert!(!game.repair_turret(0, 0));
+}
+
+#[test]
+fn events_of_a_wave() {
+    use event::Killer;
+
+    let mut game = Game::with_seed(4);
+    game.money = 1000;
+    game.add_waves(wave::parse_waves("wave\n0: 3@1", 5).unwrap());
+    game.assign_line_for_enemies();
+    game.place_turret(2, 0, 1);
+    game.start_next_wave();
+    assert_eq!(game.events(), &[Event::WaveStarted { wave: 1 }]);
+
+    let mut events = Vec::new();
+    for _ in 0..30 * FPS {
+        game.process();
+        events.extend(game.events().iter().cloned());
+    }
+    assert_eq!(events[0], Event::WaveStarted { wave: 1 });
+    assert_eq!(events[1], Event::EnemySpawned { line: 1, level: 3 });
+    assert!(events.contains(&Event::EnemyKilled {
+        line: 1,
+        level: 3,
+        reward: 15,
+        by: Killer::Turret
+    }));
+    assert_eq!(events.last(), Some(&Event::WaveCleared { wave: 1 }));
+    assert!(game.events().is_empty());
+}
+
+#[test]
+fn god_clears_the_wave() {
+    let mut game = Game::with_seed(4);
+    game.add_waves(wave::parse_waves("wave\n0: 1@1", 5).unwrap());
+    game.assign_line_for_enemies();
+    game.start_next_wave();
+    while !game.is_remaining_enemies() {
+        game.process();
+    }
+    // The first strike leaves the enemy alive
+    for _ in 0..2 {
+        game.god = GOD_CHARGED;
+        game.use_god();
+    }
+    assert_eq!(game.events().last(), Some(&Event::WaveCleared { wave: 1 }));
+    game.process();
+    game.process();
+    assert!(game.events().is_empty());
+}
+
+#[test]
+fn small_board() {
+    let mut game = Game::with_board(2, BoardSize::new(3, 4));
+    game.money = 1000;
+    game.generate_waves(2);
+    game.assign_line_for_enemies();
+
+    assert!(game.place_turret(1, 3, 2));
+    assert!(!game.place_turret(1, 4, 2));
+    assert!(!game.place_turret(1, 0, 3));
+    for _ in 0..5 {
+        game.move_player_down();
+    }
+    assert_eq!(game.player.line, 2);
+
+    game.start_next_wave();
+    for _ in 0..60 * FPS {
+        game.process();
+    }
+    assert!(game.lines.iter().all(|line| line.cells.len() == 4));
+    assert!(game.events().iter().all(|event| match event {
+        Event::EnemySpawned { line, .. } => *line < 3,
+        _ => true,
+    }));
+}
+
+#[test]
+fn terrain() {
+    use board::{Terrain, Zone};
+
+    let zone = |line, column, terrain| Zone {
+        line,
+        column,
+        terrain,
+    };
+    let map = Map {
+        size: BoardSize::default(),
+        terrain: vec![
+            zone(0, None, Terrain::Slow),
+            zone(1, None, Terrain::Fast),
+            zone(3, Some(2), Terrain::Blocked),
+        ],
+    };
+    let mut game = Game::with_map(1, &map);
+    game.money = 1000;
+    game.add_waves(wave::parse_waves("wave\n0: 1@0 1@1 1@2", 5).unwrap());
+    game.assign_line_for_enemies();
+
+    assert!(!game.place_turret(1, 2, 3));
+    assert!(game.place_turret(1, 3, 3));
+
+    game.start_next_wave();
+    for _ in 0..5 * FPS {
+        game.process();
+    }
+    let x = |y: usize| game.lines[y].enemies.borrow()[0].x();
+    assert!(x(0) > x(2) && x(2) > x(1));
+}
+
+#[test]
+fn fixed_lanes_are_kept() {
+    let mut game = Game::with_seed(11);
+    game.add_waves(wave::parse_waves("wave\n0: 1 1 2@3 3@0\n5: 4@4", 5).unwrap());
+    game.assign_line_for_enemies();
+
+    let spawns = |frame: u64| {
+        game.lines
+            .iter()
+            .map(|line| line.waves.borrow()[0].troops.get(&frame).cloned())
+            .collect::<Vec<Option<u8>>>()
+    };
+    let first = spawns(0);
+    assert_eq!((first[0], first[3]), (Some(3), Some(2)));
+    assert_eq!(first.iter().filter(|level| **level == Some(1)).count(), 2);
+    assert_eq!(spawns(5 * FPS), vec![None, None, None, None, Some(4)]);
+}
+
+#[test]
+fn extra_enemies_are_delayed() {
+    let mut game = Game::with_board(11, BoardSize::new(2, 8));
+    game.add_waves(wave::parse_waves("wave\n0: 1 2 3@0", 5).unwrap());
+    game.assign_line_for_enemies();
+
+    let troops = game
+        .lines
+        .iter()
+        .map(|line| line.waves.borrow()[0].troops.len());
+    assert_eq!(troops.sum::<usize>(), 3);
+    assert_eq!(game.lines[0].waves.borrow()[0].troops.get(&0), Some(&3));
+}
+
+#[test]
+fn level_goal() {
+    let level = &level::parse_campaign(
+        r#"[(
+            name: "Two waves",
+            map: (size: (lines: 2, columns: 3)),
+            money: 500,
+            waves: "wave\n0: 3@0\n\nwave\n0: 3@0",
+            turrets: [1],
+            unlocks: [(wave: 1, turrets: [4])],
+            goal: Waves(1),
+        )]"#,
+    )
+    .unwrap()[0];
+    let mut game = Game::from_level(3, level);
+    assert_eq!(game.money, 500);
+    assert_eq!(game.turret_list().len(), 1);
+    assert!(game.place_turret(1, 0, 0));
+
+    game.start_next_wave();
+    assert_eq!(game.turret_list().len(), 2);
+    while game.stats == GameStats::Playing && game.frame() < 60 * FPS {
+        game.process();
+    }
+    assert_eq!(game.stats, GameStats::Victory);
+    assert_eq!(game.wave(), 1);
+    assert_eq!(game.kills(), 1);
+}
+
+#[test]
+fn endless_mode() {
+    let mut game = Game::endless(5);
+    assert_eq!(game.wave_count(), 0);
+
+    for wave in 1..=3 {
+        game.skip_one_wave();
+        assert_eq!(game.wave(), wave);
+        assert_eq!(game.wave_count(), wave);
+    }
+    game.start_next_wave();
+    assert_eq!(game.wave_count(), 4);
+    assert_eq!(game.waves_cleared(), 3);
+    assert_eq!(game.score(), 3 * score::WAVE_POINTS);
+
+    for _ in 0..200 * FPS {
+        game.process();
+    }
+    assert_eq!(game.stats, GameStats::Defeat);
+    assert_eq!(game.waves_cleared(), 3);
+}
+
+#[test]
+fn switch_lane_around_a_wall() {
+    let mut game = Game::with_board(4, BoardSize::new(3, 4));
+    game.money = 1000;
+    game.add_waves(wave::parse_waves("wave\n0: 13@1", 3).unwrap());
+    game.assign_line_for_enemies();
+    assert!(game.place_turret(3, 0, 1));
+    assert!(game.place_turret(1, 0, 0));
+
+    game.start_next_wave();
+    let mut switches = Vec::new();
+    for _ in 0..40 * FPS {
+        game.process();
+        switches.extend(
+            game.events()
+                .iter()
+                .filter(|event| matches!(event, Event::EnemySwitchedLane { .. }))
+                .cloned(),
+        );
+    }
+    assert_eq!(
+        switches,
+        vec![Event::EnemySwitchedLane {
+            from: 1,
+            to: 2,
+            level: 13
+        }]
+    );
+    assert!(!game.lines[1].is_remaining_enemies());
+    assert_eq!(game.lines[2].enemies.borrow()[0].lane_shift(), 0.);
+}
+
+#[test]
+fn cross_lane_turrets() {
+    let play = |turret: u8, x: usize, y: usize, lines: &[usize]| {
+        let mut game = Game::with_board(1, BoardSize::new(3, 8));
+        game.lines[y].add_turret(x, Turret::prefab_turret(turret).unwrap());
+        for &line in lines {
+            game.lines[line].receive(Enemy::prefab(2).unwrap());
+        }
+        let mut events = Vec::new();
+        for _ in 0..40 * FPS {
+            game.process();
+            events.extend(game.events().iter().cloned());
+        }
+        let hit_lines = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::ProjectileHit { line, .. } => Some(*line),
+                _ => None,
+            })
+            .collect::<Vec<usize>>();
+        let kills = events
+            .iter()
+            .filter(|event| matches!(event, Event::EnemyKilled { .. }))
+            .count();
+        (hit_lines, kills)
+    };
+
+    let (hit_lines, kills) = play(11, 0, 1, &[0]);
+    assert!(!hit_lines.is_empty() && hit_lines.iter().all(|line| *line == 0));
+    assert_eq!(kills, 1);
+
+    let (hit_lines, _) = play(12, 4, 0, &[1, 2]);
+    assert!(hit_lines.contains(&1) && hit_lines.contains(&2));
+    assert!(!hit_lines.contains(&0));
+}
+
+#[test]
+fn bank_produces_during_waves() {
+    let mut game = Game::with_board(1, BoardSize::new(1, 8));
+    game.money = 0;
+    game.lines[0].add_turret(0, Turret::prefab_turret(13).unwrap());
+    for _ in 0..10 * FPS {
+        game.process();
+    }
+    assert_eq!(game.money, 0);
+
+    game.add_waves(wave::parse_waves("wave\n0: 2@0", 1).unwrap());
+    game.assign_line_for_enemies();
+    game.start_next_wave();
+    for _ in 0..4 * FPS {
+        game.process();
+    }
+    assert_eq!(game.money, 20);
+    assert_eq!(game.score(), 0);
+    assert!(!game.lines[0].cells[0].as_ref().unwrap().is_wall());
+}
@@ -0,0 +1,203 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::components::RangeBox;
+
+/// The geometry of the board.
+//
+// The positions on a line are in percent of its width: the player stands in the first slot,
+// followed by one slot per column, and the enemies appear at `BOARD_LENGHT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardSize {
+    pub lines: usize,
+    pub columns: usize,
+}
+
+impl Default for BoardSize {
+    fn default() -> Self {
+        Self {
+            lines: 5,
+            columns: 7,
+        }
+    }
+}
+
+impl BoardSize {
+    #[inline]
+    pub fn new(lines: usize, columns: usize) -> Self {
+        assert!(lines > 0 && columns > 0, "the board can not be empty");
+        Self { lines, columns }
+    }
+
+    /// Width of a cell, in percent of the line.
+    #[inline]
+    pub fn cell_size(&self) -> f32 {
+        100. / (self.columns + 1) as f32
+    }
+
+    /// Position of a turret placed in the column `x`.
+    #[inline]
+    pub fn turret_x(&self, x: usize) -> f32 {
+        let cell_size = self.cell_size();
+        (x + 1) as f32 * cell_size + cell_size * 12. / 25.
+    }
+
+    /// The turrets only shoot the enemies which are before this position.
+    #[inline]
+    pub fn visible_end(&self) -> f32 {
+        self.turret_x(self.columns)
+    }
+
+    #[inline]
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.columns && y < self.lines
+    }
+
+    /// The part of a line covered by the column `x`.
+    #[inline]
+    pub fn cell_bounds(&self, x: usize) -> RangeBox {
+        let cell_size = self.cell_size();
+        RangeBox::new((x + 1) as f32 * cell_size, (x + 2) as f32 * cell_size)
+    }
+
+    /// The column under the position `pos`, if any.
+    #[inline]
+    pub fn column_at(&self, pos: f32) -> Option<usize> {
+        let column = (pos / self.cell_size()).floor() - 1.;
+        if column >= 0. && (column as usize) < self.columns {
+            Some(column as usize)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Terrain {
+    /// The enemies move at half their speed
+    Slow,
+    /// The enemies move one time and a half faster
+    Fast,
+    /// No turret can be built
+    Blocked,
+    /// The turrets deal one time and a half more damage
+    Bonus,
+}
+
+impl Terrain {
+    #[inline]
+    pub fn speed_factor(&self) -> f32 {
+        match self {
+            Self::Slow => 0.5,
+            Self::Fast => 1.5,
+            _ => 1.,
+        }
+    }
+
+    #[inline]
+    pub fn damage_factor(&self) -> f32 {
+        match self {
+            Self::Bonus => 1.5,
+            _ => 1.,
+        }
+    }
+}
+
+/// A terrain covering a whole line, or only one of its cells when `column` is given.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Zone {
+    pub line: usize,
+    #[serde(default)]
+    pub column: Option<usize>,
+    pub terrain: Terrain,
+}
+
+/// The board of a level: its size and its terrain.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Map {
+    pub size: BoardSize,
+    #[serde(default)]
+    pub terrain: Vec<Zone>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapError {
+    Empty,
+    OutOfBoard { zone: usize },
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the board needs at least one line and one column"),
+            Self::OutOfBoard { zone } => {
+                write!(f, "the terrain zone {} is outside of the board", zone)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+impl Map {
+    pub fn check(&self) -> Result<(), MapError> {
+        if self.size.lines == 0 || self.size.columns == 0 {
+            return Err(MapError::Empty);
+        }
+        for (idx, zone) in self.terrain.iter().enumerate() {
+            if zone.line >= self.size.lines || zone.column.unwrap_or_default() >= self.size.columns
+            {
+                return Err(MapError::OutOfBoard { zone: idx });
+            }
+        }
+        Ok(())
+    }
+
+    /// The terrain of the line `y`, then the one of each of its cells.
+    pub fn line_terrain(&self, y: usize) -> (Option<Terrain>, Vec<Option<Terrain>>) {
+        let mut lane = None;
+        let mut cells = vec![None; self.size.columns];
+        for zone in self.terrain.iter().filter(|zone| zone.line == y) {
+            match zone.column {
+                Some(x) => cells[x] = Some(zone.terrain),
+                None => lane = Some(zone.terrain),
+            }
+        }
+        (lane, cells)
+    }
+}
+
+#[test]
+fn default_geometry() {
+    let board = BoardSize::default();
+    assert_eq!(board.cell_size(), 12.5);
+    assert_eq!(board.turret_x(0), 18.5);
+    assert_eq!(board.visible_end(), 106.);
+    assert!(board.contains(6, 4));
+    assert!(!board.contains(7, 0));
+    assert_eq!(board.column_at(12.4), None);
+    assert_eq!(board.column_at(18.5), Some(0));
+    assert_eq!(board.column_at(99.), Some(6));
+    assert_eq!(board.column_at(101.), None);
+    let bounds = board.cell_bounds(0);
+    assert_eq!((bounds.start(), bounds.end()), (12.5, 25.));
+}
+
+#[test]
+fn check_map() {
+    let map = ron::from_str::<Map>(
+        "(size: (lines: 3, columns: 4), terrain: [(line: 1, terrain: Slow), (line: 2, column: Some(3), terrain: Blocked)])",
+    )
+    .unwrap();
+    assert_eq!(map.check(), Ok(()));
+    assert_eq!(map.line_terrain(1), (Some(Terrain::Slow), vec![None; 4]));
+    assert_eq!(map.line_terrain(2).1[3], Some(Terrain::Blocked));
+
+    let mut broken = map;
+    broken.terrain[1].column = Some(4);
+    assert_eq!(
+        broken.check().unwrap_err().to_string(),
+        "the terrain zone 1 is outside of the board"
+    );
+}
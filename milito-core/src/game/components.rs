@@ -1,6 +1,8 @@
 use std::ops::Add;
 
-#[derive(Debug, Clone, Copy)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct RangeBox {
     start: f32,
     end: f32,
@@ -37,6 +39,12 @@ impl RangeBox {
     }
 }
 
+/// Scale of a sprite from its proportion of life, it shrinks faster when the unit is almost dead.
+#[inline]
+pub fn life_scale(proportion: f32) -> f32 {
+    1. / -(10f32.powf(1.7 * (proportion + 0.1))) + 1.
+}
+
 pub trait Collide<With> {
     fn collide(&self, with: With) -> bool;
 }
@@ -0,0 +1,462 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use super::components::{life_scale, Collide, RangeBox};
+use super::projectile::Projectile;
+use super::status::{Status, Statuses};
+use super::units::{units, EnemyDef};
+use super::BOARD_LENGHT;
+use crate::utils::{GetRandom, Rng};
+use crate::FPS;
+
+/// Duration of the move from a lane to another, in seconds.
+pub const LANE_SWITCH_TIME: f32 = 0.5;
+
+/// A special power of an enemy.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Ability {
+    /// Absorb this amount of damage before losing any life
+    Shield(u32),
+    /// Give `life` to the other enemies of the line closer than `range`, every `waiting` seconds
+    Healer { life: u32, range: f32, waiting: f32 },
+    /// Split into `count` enemies of the level `into` on death
+    Splitter { into: u8, count: u8 },
+    /// Leap over the first turret met
+    Jumper,
+    /// Move to an adjacent lane when blocked by a wall, at most every `waiting` seconds
+    Switcher { waiting: f32 },
+    /// Stop and fire `projectile` at the nearest turret once it is within `range`
+    Ranged { projectile: u8, range: f32 },
+}
+
+/// What a boss does when it enters a phase.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PhaseEffect {
+    /// Multiply its speed
+    SpeedUp(f32),
+    /// Call `count` enemies of the level `level` around it
+    Minions { level: u8, count: u8 },
+    /// Prevent the turrets of its lane from shooting for this amount of seconds
+    Stun(f32),
+}
+
+/// A phase of a boss, entered once its proportion of life falls under `life`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Phase {
+    pub life: f32,
+    pub effect: PhaseEffect,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Enemy {
+    life: u32,
+    max_life: f32,
+    x: f32,
+    damage: u32,
+    level: u8,
+    reward: u32,
+    speed: f32,
+    hitbox: RangeBox,
+    waiting: f32,
+    attack_waiting: f32,
+    ability: Option<Ability>,
+    /// Damage the shield can still absorb
+    shield: u32,
+    /// Frames since the last heal or change of lane
+    ability_waiting: f32,
+    jumped: bool,
+    /// Position relative to the lane during a change of lane, in lanes
+    lane_shift: f32,
+    sprite: u8,
+    /// The phases of a boss, by decreasing life
+    phases: Vec<Phase>,
+    /// Number of phases entered
+    phase: usize,
+    statuses: Statuses,
+}
+
+impl Enemy {
+    #[inline]
+    pub fn prefab(level: u8) -> Option<Self> {
+        units().enemy(level).map(EnemyDef::build)
+    }
+
+    #[inline]
+    pub fn new(
+        life: u32,
+        damage: u32,
+        level: u8,
+        reward: u32,
+        speed: f32,
+        hitbox: RangeBox,
+        attack_waiting: f32,
+    ) -> Self {
+        Self {
+            life,
+            max_life: life as f32,
+            x: BOARD_LENGHT,
+            damage,
+            level,
+            reward,
+            speed: speed / FPS as f32,
+            hitbox,
+            waiting: attack_waiting * FPS as f32,
+            attack_waiting: attack_waiting * FPS as f32,
+            ability: None,
+            shield: 0,
+            ability_waiting: 0.,
+            jumped: false,
+            lane_shift: 0.,
+            sprite: level,
+            phases: Vec::new(),
+            phase: 0,
+            statuses: Statuses::default(),
+        }
+    }
+
+    #[inline]
+    pub fn with_ability(mut self, ability: Option<Ability>) -> Self {
+        self.shield = match ability {
+            Some(Ability::Shield(amount)) => amount,
+            _ => 0,
+        };
+        self.ability = ability;
+        self
+    }
+
+    /// Draw the enemy with the sprite of the level `sprite`.
+    #[inline]
+    pub fn with_sprite(mut self, sprite: u8) -> Self {
+        self.sprite = sprite;
+        self
+    }
+
+    /// Make the enemy a boss with these phases.
+    #[inline]
+    pub fn with_phases(mut self, phases: Vec<Phase>) -> Self {
+        self.phases = phases;
+        self
+    }
+
+    #[inline]
+    pub fn is_boss(&self) -> bool {
+        !self.phases.is_empty()
+    }
+
+    #[inline]
+    pub fn phases(&self) -> &[Phase] {
+        &self.phases
+    }
+
+    /// Number of phases entered.
+    #[inline]
+    pub fn phase(&self) -> usize {
+        self.phase
+    }
+
+    /// Enter the next phase if the life is low enough, the speed-ups are applied right away.
+    pub fn next_phase(&mut self) -> Option<PhaseEffect> {
+        let phase = self.phases.get(self.phase)?;
+        if self.is_dead() || self.health() > phase.life {
+            return None;
+        }
+        self.phase += 1;
+        if let PhaseEffect::SpeedUp(factor) = phase.effect {
+            self.speed *= factor;
+        }
+        Some(phase.effect)
+    }
+
+    /// The enemies called by a boss, behind it.
+    pub fn minions(&self, level: u8, count: u8) -> Vec<Enemy> {
+        (0..count)
+            .filter_map(|i| {
+                let mut enemy = Enemy::prefab(level)?;
+                enemy.x = self.hitbox().end() + i as f32 * 3.;
+                Some(enemy)
+            })
+            .collect()
+    }
+
+    #[inline]
+    pub fn ability(&self) -> Option<Ability> {
+        self.ability
+    }
+
+    #[inline]
+    pub fn shield(&self) -> u32 {
+        self.shield
+    }
+
+    #[inline]
+    pub fn sprite(&self) -> u8 {
+        self.sprite
+    }
+
+    #[inline]
+    pub fn x(&self) -> f32 {
+        self.x
+    }
+
+    #[inline]
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    #[inline]
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    #[inline]
+    pub fn reward(&self) -> u32 {
+        self.reward
+    }
+
+    /// Life points left.
+    #[inline]
+    pub fn life(&self) -> u32 {
+        self.life
+    }
+
+    /// Proportion of life left, between 0 and 1.
+    #[inline]
+    pub fn health(&self) -> f32 {
+        self.life as f32 / self.max_life
+    }
+
+    #[inline]
+    pub fn scale(&self) -> f32 {
+        life_scale(self.health())
+    }
+
+    /// Advance of a frame, return the damage of the burns.
+    #[inline]
+    pub fn wait(&mut self) -> u32 {
+        let burn = self.statuses.tick();
+        if self.statuses.can_attack() {
+            self.waiting =
+                (self.waiting + 1. / self.statuses.attack_factor()).min(self.attack_waiting);
+        }
+        if let Some(Ability::Switcher { waiting }) = self.ability {
+            self.ability_waiting = (self.ability_waiting + 1.).min(waiting * FPS as f32);
+        }
+        let step = 1. / (LANE_SWITCH_TIME * FPS as f32);
+        self.lane_shift = (self.lane_shift.abs() - step).max(0.) * self.lane_shift.signum();
+        self.take_damage(burn)
+    }
+
+    #[inline]
+    pub fn can_attack(&self) -> bool {
+        (self.waiting - self.attack_waiting).abs() < f32::EPSILON && self.statuses.can_attack()
+    }
+
+    #[inline]
+    pub fn apply_status(&mut self, status: Status) {
+        self.statuses.apply(status)
+    }
+
+    #[inline]
+    pub fn statuses(&self) -> &Statuses {
+        &self.statuses
+    }
+
+    #[inline]
+    pub fn attack(&mut self) -> u32 {
+        self.waiting = 0.;
+        self.damage
+    }
+
+    /// Distance from which the enemy shoots, for the ranged ones.
+    #[inline]
+    pub fn range(&self) -> Option<f32> {
+        match self.ability {
+            Some(Ability::Ranged { range, .. }) => Some(range),
+            _ => None,
+        }
+    }
+
+    pub fn shoot(&mut self) -> Option<Projectile> {
+        match self.ability {
+            Some(Ability::Ranged { projectile, .. }) if self.can_attack() => {
+                self.waiting = 0.;
+                Projectile::new_enemy_projectile(projectile, self.hitbox().start())
+            }
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn hitbox(&self) -> RangeBox {
+        self.hitbox + self.x
+    }
+
+    /// Return the damage which went through the shield.
+    #[inline]
+    pub fn take_damage(&mut self, damage: u32) -> u32 {
+        let absorbed = damage.min(self.shield);
+        self.shield -= absorbed;
+        self.life = self.life.saturating_sub(damage - absorbed);
+        damage - absorbed
+    }
+
+    #[inline]
+    pub fn heal(&mut self, life: u32) {
+        self.life = (self.life + life).min(self.max_life as u32)
+    }
+
+    /// The heal to give to the enemies around once it is ready, as `(life, range)`.
+    pub fn heal_ready(&mut self) -> Option<(u32, f32)> {
+        if let Some(Ability::Healer {
+            life,
+            range,
+            waiting,
+        }) = self.ability
+        {
+            self.ability_waiting += 1.;
+            if self.ability_waiting >= waiting * FPS as f32 {
+                self.ability_waiting = 0.;
+                return Some((life, range));
+            }
+        }
+        None
+    }
+
+    /// The enemies which appear on its death.
+    pub fn split(&self) -> Vec<Enemy> {
+        match self.ability {
+            Some(Ability::Splitter { into, count }) => (0..count)
+                .filter_map(|i| {
+                    let mut enemy = Enemy::prefab(into)?;
+                    enemy.x = self.x + i as f32 * 2.;
+                    Some(enemy)
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether the enemy leaves its lane rather than attacking the wall in front of it.
+    #[inline]
+    pub fn can_switch_lane(&self) -> bool {
+        matches!(self.ability, Some(Ability::Switcher { waiting }) if self.ability_waiting >= waiting * FPS as f32)
+            && !self.statuses.is_frozen()
+    }
+
+    /// The enemy arrives from the lane at `from` lanes of the new one, -1 for the lane above.
+    #[inline]
+    pub fn switch_lane(&mut self, from: f32) {
+        self.ability_waiting = 0.;
+        self.lane_shift = from;
+    }
+
+    #[inline]
+    pub fn lane_shift(&self) -> f32 {
+        self.lane_shift
+    }
+
+    /// Leap over the turret of hitbox `over`, return false if the enemy can not jump.
+    pub fn jump(&mut self, over: RangeBox) -> bool {
+        if self.ability != Some(Ability::Jumper) || self.jumped || self.statuses.is_frozen() {
+            return false;
+        }
+        self.jumped = true;
+        // Land just behind the turret
+        self.x = over.start() - self.hitbox.end() - 0.1;
+        true
+    }
+
+    #[inline]
+    pub fn is_dead(&self) -> bool {
+        self.life == 0
+    }
+
+    /// Move the enemy, `speed_factor` comes from the terrain under it.
+    #[inline]
+    pub fn deplace(&mut self, speed_factor: f32) {
+        self.x += self.speed * speed_factor * self.statuses.speed_factor();
+    }
+
+    #[inline]
+    pub fn max_life(&self) -> f32 {
+        self.max_life
+    }
+
+    #[inline]
+    pub fn css_transform(&self) -> String {
+        let x_shift = -20. * (1. - 2. * self.waiting / self.attack_waiting).max(0.);
+        format!(
+            "transform:matrix({scale},0.00,0.00,{scale},{x_shift},0);",
+            scale = self.scale(),
+            x_shift = x_shift
+        )
+    }
+}
+
+impl PartialEq for Enemy {
+    fn eq(&self, other: &Self) -> bool {
+        self.x == other.x
+    }
+}
+
+impl Eq for Enemy {}
+
+impl PartialOrd for Enemy {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Enemy {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.x.partial_cmp(&other.x).unwrap()
+    }
+}
+
+impl Collide<&Projectile> for &Enemy {
+    #[inline]
+    fn collide(&self, with: &Projectile) -> bool {
+        self.hitbox().collide(&with.hitbox())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EnemyProceced {
+    pub level: u8,
+    pub weight: u32,
+    pub ability: Option<Ability>,
+}
+
+impl EnemyProceced {
+    pub fn new_random(rng: &mut Rng, proba_rank2_enemy: f64) -> Self {
+        let rank = Self::random_rank(rng, proba_rank2_enemy);
+        units().rank(rank).get_random(rng).unwrap().clone()
+    }
+
+    /// A random enemy which is not a healer, every rank has one.
+    pub fn new_random_fighter(rng: &mut Rng, proba_rank2_enemy: f64) -> Self {
+        let rank = Self::random_rank(rng, proba_rank2_enemy);
+        let units = units();
+        let fighters = units
+            .rank(rank)
+            .iter()
+            .filter(|enemy| !enemy.is_healer())
+            .collect::<Vec<&EnemyProceced>>();
+        (*fighters.get_random(rng).unwrap()).clone()
+    }
+
+    #[inline]
+    pub fn is_healer(&self) -> bool {
+        matches!(self.ability, Some(Ability::Healer { .. }))
+    }
+
+    #[inline]
+    fn random_rank(rng: &mut Rng, proba_rank2_enemy: f64) -> u8 {
+        if rng.next_f64() > proba_rank2_enemy {
+            1
+        } else {
+            2
+        }
+    }
+}
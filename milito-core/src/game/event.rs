@@ -0,0 +1,85 @@
+use super::projectile::Owner;
+use super::Reward;
+
+/// Something which happened in the game, see `Game::events`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    EnemySpawned {
+        line: usize,
+        level: u8,
+    },
+    EnemyKilled {
+        line: usize,
+        level: u8,
+        reward: Reward,
+        by: Killer,
+    },
+    TurretDestroyed {
+        line: usize,
+        x: usize,
+        level: u8,
+    },
+    ProjectileFired {
+        line: usize,
+        level: u8,
+        owner: Owner,
+    },
+    ProjectileHit {
+        line: usize,
+        level: u8,
+        damage: u32,
+        /// Position of the unit hit
+        x: f32,
+    },
+    /// Damage of the burns on an enemy
+    Burned {
+        line: usize,
+        damage: u32,
+        x: f32,
+    },
+    /// A turret of `line` struck its whole column `x`, on every line
+    BeamFired {
+        line: usize,
+        x: usize,
+        level: u8,
+    },
+    EnemySwitchedLane {
+        from: usize,
+        to: usize,
+        level: u8,
+    },
+    /// A boss entered its phase `phase`, counted from 1
+    BossPhase {
+        line: usize,
+        level: u8,
+        phase: usize,
+    },
+    /// Money produced by the turret at `x`
+    MoneyProduced {
+        line: usize,
+        x: usize,
+        money: Reward,
+    },
+    /// An enemy reached the kingdom, the game is lost
+    LaneBreached {
+        line: usize,
+        level: u8,
+    },
+    WaveStarted {
+        wave: usize,
+    },
+    /// Every enemy of the wave has been killed
+    WaveCleared {
+        wave: usize,
+    },
+    GodUsed {
+        reward: Reward,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Killer {
+    Turret,
+    Player,
+    God,
+}
@@ -0,0 +1,268 @@
+use std::fmt;
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    board::{Map, MapError},
+    units::units,
+    wave::{parse_waves, Wave, WaveScriptError, WAVES},
+};
+
+lazy_static! {
+    /// The levels of the campaign, in the order they must be completed.
+    pub static ref CAMPAIGN: Vec<Level> = parse_campaign(include_str!("../../data/campaign.ron"))
+        .expect("the campaign is invalid");
+}
+
+/// Turrets added to the shop when the wave `wave` starts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Unlock {
+    pub wave: usize,
+    pub turrets: Vec<u8>,
+}
+
+/// The unlocks of the standard game: the bank at the second wave, the frost turret and the
+/// ballista at the fifth wave, the shotgun and the sniper at the seventh wave, then the upgraded
+/// turrets, the catapult and the tesla at the tenth wave, the mortar and the column laser at the
+/// twelfth wave.
+pub fn standard_unlocks() -> Vec<Unlock> {
+    vec![
+        Unlock {
+            wave: 2,
+            turrets: vec![13],
+        },
+        Unlock {
+            wave: 5,
+            turrets: vec![7, 8],
+        },
+        Unlock {
+            wave: 7,
+            turrets: vec![14, 15],
+        },
+        Unlock {
+            wave: 10,
+            turrets: vec![4, 5, 6, 9, 10],
+        },
+        Unlock {
+            wave: 12,
+            turrets: vec![11, 12],
+        },
+    ]
+}
+
+/// What must be done to win a level.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Goal {
+    /// Kill every enemy of every wave
+    #[default]
+    AllWaves,
+    /// Clear the given number of waves
+    Waves(usize),
+    /// Own the given amount of money
+    Money(u32),
+    /// The waves never end, the game only ends with a defeat
+    Endless,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Level {
+    pub name: String,
+    pub map: Map,
+    pub money: u32,
+    pub waves: Vec<Wave>,
+    /// Number of waves generated after the scripted ones
+    pub generated: u32,
+    /// The turrets in the shop at the start of the level
+    pub turrets: Vec<u8>,
+    pub unlocks: Vec<Unlock>,
+    pub goal: Goal,
+}
+
+impl Level {
+    /// The free play: the prefab waves followed by five generated ones.
+    pub fn standard() -> Self {
+        Self {
+            name: "Free play".to_owned(),
+            map: Map::default(),
+            money: if cfg!(debug_assertions) { 99999 } else { 0 },
+            waves: WAVES.clone(),
+            generated: 5,
+            turrets: vec![1, 2, 3],
+            unlocks: standard_unlocks(),
+            goal: Goal::AllWaves,
+        }
+    }
+
+    /// Generated waves of rising level, until the defeat.
+    pub fn endless() -> Self {
+        Self {
+            name: "Endless".to_owned(),
+            waves: Vec::new(),
+            generated: 0,
+            goal: Goal::Endless,
+            ..Self::standard()
+        }
+    }
+
+    #[inline]
+    pub fn wave_count(&self) -> usize {
+        self.waves.len() + self.generated as usize
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LevelError {
+    Parse(String),
+    Map {
+        level: String,
+        error: MapError,
+    },
+    Waves {
+        level: String,
+        error: WaveScriptError,
+    },
+    NoWave(String),
+    UnknownTurret {
+        level: String,
+        turret: u8,
+    },
+    UnreachableGoal(String),
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "{}", err),
+            Self::Map { level, error } => write!(f, "level \"{}\": {}", level, error),
+            Self::Waves { level, error } => write!(f, "level \"{}\", waves {}", level, error),
+            Self::NoWave(level) => write!(f, "level \"{}\" has no wave", level),
+            Self::UnknownTurret { level, turret } => {
+                write!(
+                    f,
+                    "level \"{}\" references unknown turret {}",
+                    level, turret
+                )
+            }
+            Self::UnreachableGoal(level) => {
+                write!(f, "level \"{}\" has more waves to clear than waves", level)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+#[derive(Deserialize)]
+struct LevelFile {
+    name: String,
+    #[serde(default)]
+    map: Map,
+    money: u32,
+    /// A wave script, see `data/waves.txt`
+    #[serde(default)]
+    waves: String,
+    #[serde(default)]
+    generated: u32,
+    turrets: Vec<u8>,
+    #[serde(default)]
+    unlocks: Vec<Unlock>,
+    #[serde(default)]
+    goal: Goal,
+}
+
+/// Parse the levels of a campaign, its format is the one of `data/campaign.ron`.
+pub fn parse_campaign(text: &str) -> Result<Vec<Level>, LevelError> {
+    let files =
+        ron::from_str::<Vec<LevelFile>>(text).map_err(|err| LevelError::Parse(err.to_string()))?;
+    files.into_iter().map(check_level).collect()
+}
+
+fn check_level(file: LevelFile) -> Result<Level, LevelError> {
+    let name = file.name;
+    file.map.check().map_err(|error| LevelError::Map {
+        level: name.clone(),
+        error,
+    })?;
+
+    let waves =
+        parse_waves(&file.waves, file.map.size.lines).map_err(|error| LevelError::Waves {
+            level: name.clone(),
+            error,
+        })?;
+
+    let units = units();
+    let turrets = file
+        .turrets
+        .iter()
+        .chain(file.unlocks.iter().flat_map(|unlock| unlock.turrets.iter()));
+    for &turret in turrets {
+        if units.turret(turret).is_none() {
+            return Err(LevelError::UnknownTurret {
+                level: name,
+                turret,
+            });
+        }
+    }
+
+    let level = Level {
+        name,
+        map: file.map,
+        money: file.money,
+        waves,
+        generated: file.generated,
+        turrets: file.turrets,
+        unlocks: file.unlocks,
+        goal: file.goal,
+    };
+    if level.wave_count() == 0 && level.goal != Goal::Endless {
+        return Err(LevelError::NoWave(level.name));
+    }
+    match level.goal {
+        Goal::Waves(amount) if amount == 0 || amount > level.wave_count() => {
+            Err(LevelError::UnreachableGoal(level.name))
+        }
+        _ => Ok(level),
+    }
+}
+
+#[test]
+fn default_campaign() {
+    assert!(!CAMPAIGN.is_empty());
+}
+
+#[test]
+fn check_levels() {
+    let levels = parse_campaign(
+        r#"[(
+            name: "Small",
+            map: (size: (lines: 2, columns: 3)),
+            money: 100,
+            waves: "wave\n0: 1@1",
+            turrets: [1],
+            goal: Waves(1),
+        )]"#,
+    )
+    .unwrap();
+    assert_eq!(levels[0].waves.len(), 1);
+    assert_eq!(levels[0].goal, Goal::Waves(1));
+    assert!(levels[0].unlocks.is_empty());
+
+    let error = |text: &str| parse_campaign(text).unwrap_err().to_string();
+    assert_eq!(
+        error(r#"[(name: "A", money: 0, waves: "wave\n0: 1@5", turrets: [1])]"#),
+        "level \"A\", waves line 2, column 4: invalid lane \"5\""
+    );
+    assert_eq!(
+        error(r#"[(name: "B", money: 0, generated: 1, turrets: [42])]"#),
+        "level \"B\" references unknown turret 42"
+    );
+    assert_eq!(
+        error(r#"[(name: "C", money: 0, turrets: [1])]"#),
+        "level \"C\" has no wave"
+    );
+    assert_eq!(
+        error(r#"[(name: "D", money: 0, generated: 2, turrets: [1], goal: Waves(3))]"#),
+        "level \"D\" has more waves to clear than waves"
+    );
+}
@@ -0,0 +1,1096 @@
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use super::board::{BoardSize, Terrain};
+use super::components::{Collide, RangeBox};
+use super::enemy::{Enemy, PhaseEffect};
+use super::event::{Event, Killer};
+use super::projectile::{Behaviour, Owner, Projectile};
+use super::status::{Effect, Status};
+use super::turret::{Priority, Turret};
+use super::wave::{IteratorWaveLine, WaveLine};
+use super::{Reward, BOARD_LENGHT};
+
+/// An enemy going further than this position has reached the kingdom.
+pub const LANE_END: f32 = -10.;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Line {
+    board: BoardSize,
+    terrain: Option<Terrain>,
+    cells_terrain: Vec<Option<Terrain>>,
+    pub cells: Vec<Option<Turret>>,
+    pub projectiles: RefCell<Vec<Projectile>>,
+    pub enemies: RefCell<Vec<Enemy>>,
+    pub waves: RefCell<VecDeque<WaveLine>>,
+    current_wave: Option<RefCell<IteratorWaveLine>>,
+    /// The enemies leaving the line, moved by `Game` once every line is processed
+    #[serde(skip)]
+    leaving: Vec<Enemy>,
+}
+
+impl Line {
+    pub fn new(board: BoardSize) -> Self {
+        Self {
+            board,
+            terrain: None,
+            cells_terrain: vec![None; board.columns],
+            cells: vec![None; board.columns],
+            projectiles: RefCell::new(Vec::new()),
+            enemies: RefCell::new(Vec::new()),
+            waves: RefCell::new(VecDeque::new()),
+            current_wave: None,
+            leaving: Vec::new(),
+        }
+    }
+
+    pub(crate) fn skip_one_wave(&mut self) -> u32 {
+        let mut wave = self
+            .waves
+            .borrow_mut()
+            .pop_front()
+            .unwrap_or_default()
+            .into_iter();
+
+        let mut reward = 0;
+        while !wave.is_ended() {
+            if let Some(enemy) = wave.next() {
+                reward += enemy.reward()
+            }
+        }
+        reward
+    }
+
+    pub fn delete_turret(&mut self, x: usize) -> u32 {
+        let mut refund = 0;
+        if let Some(ref turret) = self.cells[x] {
+            refund = turret.refund()
+        }
+        self.cells[x] = None;
+        refund
+    }
+
+    #[inline]
+    pub fn set_terrain(&mut self, terrain: Option<Terrain>, cells_terrain: Vec<Option<Terrain>>) {
+        self.terrain = terrain;
+        self.cells_terrain = cells_terrain;
+    }
+
+    /// The terrain of the line, which applies to each cell without its own terrain.
+    #[inline]
+    pub fn terrain(&self) -> Option<Terrain> {
+        self.terrain
+    }
+
+    #[inline]
+    pub fn cells_terrain(&self) -> &[Option<Terrain>] {
+        &self.cells_terrain
+    }
+
+    /// The terrains at the cell `x`: the one of the line and the one of the cell.
+    #[inline]
+    fn terrains_at(&self, x: usize) -> impl Iterator<Item = Terrain> + '_ {
+        self.terrain
+            .iter()
+            .chain(self.cells_terrain.get(x).into_iter().flatten())
+            .copied()
+    }
+
+    #[inline]
+    pub fn is_buildable(&self, x: usize) -> bool {
+        self.cells[x].is_none() && !self.terrains_at(x).any(|t| t == Terrain::Blocked)
+    }
+
+    #[inline]
+    fn speed_factor(&self, pos: f32) -> f32 {
+        let lane = self.terrain.map_or(1., |t| t.speed_factor());
+        let cell = self
+            .board
+            .column_at(pos)
+            .and_then(|x| self.cells_terrain[x])
+            .map_or(1., |t| t.speed_factor());
+        lane * cell
+    }
+
+    pub fn add_turret(&mut self, x: usize, turret: Turret) -> u32 {
+        let mut price = 0;
+        if self.is_buildable(x) {
+            price = turret.price();
+            self.cells[x] = Some(turret.set_x(self.board.turret_x(x)))
+        }
+        price
+    }
+
+    /// Replace the turret by its upgrade and return the difference of price.
+    pub fn upgrade_turret(&mut self, x: usize) -> u32 {
+        let mut cost = 0;
+        if let Some(turret) = self.cells[x].as_ref() {
+            if let Some(upgraded) = turret.upgraded() {
+                cost = upgraded.price().saturating_sub(turret.price());
+                self.cells[x] = Some(upgraded);
+            }
+        }
+        cost
+    }
+
+    /// Restore the life of the turret and return the price of the repair.
+    pub fn repair_turret(&mut self, x: usize) -> u32 {
+        let mut cost = 0;
+        if let Some(turret) = self.cells[x].as_mut() {
+            cost = turret.repair_cost();
+            turret.repair();
+        }
+        cost
+    }
+
+    #[inline]
+    /// Queue the waves after the ones not started yet.
+    pub fn add_waves(&mut self, waves: VecDeque<WaveLine>) {
+        self.waves.borrow_mut().extend(waves)
+    }
+
+    #[inline]
+    pub fn spawn_projectile(&mut self, projectile: Projectile) {
+        self.projectiles.borrow_mut().push(projectile)
+    }
+
+    #[inline]
+    pub fn spawn_projectiles<I: IntoIterator<Item = Projectile>>(&mut self, projectiles: I) {
+        self.projectiles.borrow_mut().extend(projectiles)
+    }
+
+    /// `y` is the index of the line, used by the events.
+    #[inline]
+    pub fn use_god(&mut self, y: usize, events: &mut Vec<Event>) -> Reward {
+        let dead_enemies = self
+            .enemies
+            .borrow_mut()
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(i, enemy)| {
+                enemy.take_damage((enemy.max_life() / 1.25) as u32);
+                if enemy.is_dead() {
+                    Some(i)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<usize>>();
+
+        let mut reward = 0;
+        for index in dead_enemies.into_iter().rev() {
+            let enemy = self.remove_enemy(index);
+            events.push(Event::EnemyKilled {
+                line: y,
+                level: enemy.level(),
+                reward: enemy.reward(),
+                by: Killer::God,
+            });
+            reward += enemy.reward();
+            self.spawn_split(y, &enemy, events);
+        }
+        reward
+    }
+
+    /// Add the enemies appearing on the death of `enemy`.
+    fn spawn_split(&mut self, y: usize, enemy: &Enemy, events: &mut Vec<Event>) {
+        for child in enemy.split() {
+            events.push(Event::EnemySpawned {
+                line: y,
+                level: child.level(),
+            });
+            self.enemies.borrow_mut().push(child);
+        }
+    }
+
+    #[inline]
+    pub fn start_next_wave(&mut self) -> Option<()> {
+        self.current_wave = Some(RefCell::new(
+            self.waves.borrow_mut().pop_front()?.into_iter(),
+        ));
+        Some(())
+    }
+
+    #[inline]
+    pub fn is_wave_ended(&self) -> bool {
+        if let Some(wave) = &self.current_wave {
+            wave.borrow().is_ended()
+        } else {
+            true
+        }
+    }
+
+    #[inline]
+    pub(crate) fn take_leaving(&mut self) -> Vec<Enemy> {
+        std::mem::take(&mut self.leaving)
+    }
+
+    /// Add an enemy coming from another line.
+    #[inline]
+    pub(crate) fn receive(&mut self, enemy: Enemy) {
+        self.enemies.borrow_mut().push(enemy)
+    }
+
+    /// Number of turrets an enemy at `x` still has to pass.
+    #[inline]
+    pub fn defense_before(&self, x: f32) -> usize {
+        self.cells
+            .iter()
+            .flatten()
+            .filter(|turret| turret.hitbox().start() < x)
+            .count()
+    }
+
+    /// Distance from `x` to the nearest turret in front of it.
+    fn turret_distance(&self, x: f32) -> Option<f32> {
+        self.cells
+            .iter()
+            .flatten()
+            .map(|turret| x - turret.hitbox().end())
+            .filter(|distance| *distance >= 0.)
+            .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+    }
+
+    /// Position of the most advanced enemy in front of `x`, which is visible.
+    pub(crate) fn most_advanced(&self, x: f32) -> Option<f32> {
+        let visible_end = self.board.visible_end();
+        self.enemies_coord()
+            .into_iter()
+            .filter(|coord| x <= *coord && *coord < visible_end)
+            .min_by(|coord1, coord2| coord1.partial_cmp(coord2).unwrap())
+    }
+
+    #[inline]
+    pub(crate) fn is_enemy_in(&self, column: RangeBox) -> bool {
+        self.enemies
+            .borrow()
+            .iter()
+            .any(|enemy| column.collide(&enemy.hitbox()))
+    }
+
+    #[inline]
+    fn damage_factor(&self, x: usize) -> f32 {
+        self.terrains_at(x).map(|t| t.damage_factor()).product()
+    }
+
+    /// Make the turrets with an income produce, during a frame of wave.
+    pub(crate) fn produce(&mut self, y: usize, events: &mut Vec<Event>) {
+        for (x, turret) in self.cells.iter_mut().enumerate() {
+            if let Some(money) = turret.as_mut().and_then(Turret::produce) {
+                events.push(Event::MoneyProduced { line: y, x, money });
+            }
+        }
+    }
+
+    /// Shoot with the turret reaching other lines at `x`, if it is ready.
+    pub(crate) fn fire(
+        &mut self,
+        x: usize,
+        y: usize,
+        events: &mut Vec<Event>,
+    ) -> Option<Projectile> {
+        let damage_factor = self.damage_factor(x);
+        let mut projectile = self.cells[x].as_mut()?.shoot()?;
+        projectile.amplify(damage_factor);
+        events.push(Event::ProjectileFired {
+            line: y,
+            level: projectile.level(),
+            owner: Owner::Turret,
+        });
+        Some(projectile)
+    }
+
+    /// Hit every enemy in `column` with `projectile`, without moving it.
+    pub(crate) fn strike(
+        &mut self,
+        column: RangeBox,
+        projectile: &Projectile,
+        y: usize,
+        events: &mut Vec<Event>,
+    ) {
+        let mut dead_enemies = Vec::new();
+        for (idx, enemy) in self.enemies.borrow_mut().iter_mut().enumerate() {
+            if column.collide(&enemy.hitbox()) {
+                let damage = enemy.take_damage(projectile.damage());
+                if let Some(status) = projectile.status() {
+                    enemy.apply_status(status);
+                }
+                events.push(Event::ProjectileHit {
+                    line: y,
+                    level: projectile.level(),
+                    damage,
+                    x: enemy.x(),
+                });
+                if enemy.is_dead() {
+                    dead_enemies.push(idx);
+                }
+            }
+        }
+        let mut split = Vec::new();
+        for index in dead_enemies.into_iter().rev() {
+            let enemy = self.remove_enemy(index);
+            events.push(Event::EnemyKilled {
+                line: y,
+                level: enemy.level(),
+                reward: enemy.reward(),
+                by: Killer::Turret,
+            });
+            split.push(enemy);
+        }
+        for enemy in split {
+            self.spawn_split(y, &enemy, events);
+        }
+    }
+
+    /// Remove the enemy without breaking the impacts planned by the projectiles.
+    fn remove_enemy(&mut self, index: usize) -> Enemy {
+        for projectile in self.projectiles.borrow_mut().iter_mut() {
+            projectile.enemy_removed(index)
+        }
+        self.enemies.borrow_mut().remove(index)
+    }
+
+    #[inline]
+    pub fn is_remaining_enemies(&self) -> bool {
+        !self.enemies.borrow().is_empty()
+    }
+
+    #[inline]
+    fn enemies_coord(&self) -> Vec<f32> {
+        self.enemies
+            .borrow()
+            .iter()
+            .map(|e| e.hitbox().end())
+            .collect::<Vec<f32>>()
+    }
+
+    /// Process a frame, `y` is the index of the line used by the events.
+    pub fn process(&mut self, y: usize, events: &mut Vec<Event>) {
+        self.process_projectiles(y, events);
+
+        self.process_enemies(y, events);
+
+        self.process_turrets(y, events);
+
+        self.spawn_new_enemies(y, events);
+    }
+
+    fn process_projectiles(&mut self, y: usize, events: &mut Vec<Event>) {
+        let mut buf_attack = Vec::new();
+        let mut del_projs = Vec::new();
+        let mut move_projs = Vec::new();
+        let mut dead_enemies = Vec::new();
+
+        self.projectiles
+            .borrow_mut()
+            .iter_mut()
+            .enumerate()
+            .for_each(|(proj_index, proj)| {
+                if let Some(impact_idx) = proj.take_next_impact() {
+                    buf_attack.push((proj_index, impact_idx));
+                } else {
+                    move_projs.push(proj_index);
+                }
+            });
+
+        buf_attack.sort_unstable_by(|(_, enmy1), (_, enmy2)| enmy2.cmp(enmy1));
+
+        for (proj_index, enemy_index) in buf_attack {
+            let mut projectiles = self.projectiles.borrow_mut();
+            let projectile = &mut projectiles[proj_index];
+            let mut enemies = self.enemies.borrow_mut();
+
+            // Killed by a previous projectile of the frame, this one keeps flying
+            let is_dead = |idx: usize| dead_enemies.iter().any(|(dead, _)| *dead == idx);
+            if is_dead(enemy_index) {
+                move_projs.push(proj_index);
+                continue;
+            }
+
+            let by = match projectile.owner() {
+                Owner::Player => Killer::Player,
+                _ => Killer::Turret,
+            };
+            for (idx, damage) in impacts(projectile, enemy_index, &enemies, is_dead) {
+                let enemy = &mut enemies[idx];
+                let damage = enemy.take_damage(damage);
+                if let Some(status) = projectile.status() {
+                    enemy.apply_status(status);
+                }
+                events.push(Event::ProjectileHit {
+                    line: y,
+                    level: projectile.level(),
+                    damage,
+                    x: enemy.x(),
+                });
+                if enemy.is_dead() {
+                    dead_enemies.push((idx, by))
+                }
+            }
+
+            if projectile.pierce(enemies[enemy_index].hitbox().end()) {
+                move_projs.push(proj_index);
+            } else {
+                del_projs.push(proj_index);
+            }
+        }
+
+        dead_enemies.sort_unstable_by(|(enmy1, _), (enmy2, _)| enmy2.cmp(enmy1));
+        let mut split = Vec::new();
+        for (dead_index, by) in dead_enemies {
+            let enemy = self.remove_enemy(dead_index);
+            events.push(Event::EnemyKilled {
+                line: y,
+                level: enemy.level(),
+                reward: enemy.reward(),
+                by,
+            });
+            split.push(enemy);
+        }
+
+        let mut turret_hits = Vec::new();
+        {
+            let mut projectiles = self.projectiles.borrow_mut();
+            for proj_index in move_projs {
+                let proj = projectiles.get_mut(proj_index).unwrap();
+                if proj.owner() == Owner::Enemy {
+                    // The turret on the way of the projectile during this frame is hit
+                    let path =
+                        RangeBox::new(proj.hitbox().start() + proj.speed(), proj.hitbox().end());
+                    let hit = self
+                        .cells
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(x, turret)| Some((x, turret.as_ref()?.hitbox())))
+                        .filter(|(_, hitbox)| {
+                            hitbox.end() >= path.start() && hitbox.start() <= path.end()
+                        })
+                        .max_by(|(_, hitbox1), (_, hitbox2)| {
+                            hitbox1.end().partial_cmp(&hitbox2.end()).unwrap()
+                        });
+                    if let Some((x, _)) = hit {
+                        turret_hits.push((proj_index, x));
+                        continue;
+                    }
+                } else if let Some(idx) = self
+                    .enemies
+                    .borrow()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, enemy)| enemy.hitbox().end() >= proj.hitbox().start())
+                    .filter(|(idx, enemy)| proj.can_hit(*idx, enemy.hitbox().end()))
+                    .min_by(|(_, enmy1), (_, enmy2)| {
+                        (enmy1.hitbox().start() - proj.hitbox().end())
+                            .partial_cmp(&(enmy2.hitbox().start() - proj.hitbox().end()))
+                            .unwrap()
+                    })
+                    .and_then(|(idx, enemy)| {
+                        if (enemy.hitbox().start() + enemy.speed())
+                            - (proj.hitbox().end() + proj.speed())
+                            <= 0.
+                        {
+                            Some(idx)
+                        } else {
+                            None
+                        }
+                    })
+                {
+                    proj.add_next_impact(idx)
+                }
+                proj.deplace();
+                if proj.x() > BOARD_LENGHT || proj.x() < LANE_END || proj.is_out_of_range() {
+                    del_projs.push(proj_index)
+                }
+            }
+
+            for (proj_index, x) in turret_hits {
+                let proj = projectiles.get_mut(proj_index).unwrap();
+                match self.cells[x].as_mut() {
+                    Some(turret) => {
+                        let damage = turret.take_damage(proj.damage());
+                        if let Some(status) = proj.status() {
+                            turret.apply_status(status);
+                        }
+                        events.push(Event::ProjectileHit {
+                            line: y,
+                            level: proj.level(),
+                            damage,
+                            x: turret.hitbox().start(),
+                        });
+                        del_projs.push(proj_index);
+                        if turret.is_dead() {
+                            events.push(Event::TurretDestroyed {
+                                line: y,
+                                x,
+                                level: turret.level(),
+                            });
+                            self.cells[x] = None;
+                        }
+                    }
+                    // Destroyed by a previous projectile of the frame
+                    None => proj.deplace(),
+                }
+            }
+        }
+
+        del_projs.sort_unstable_by(|proj1, proj2| proj2.cmp(proj1));
+        {
+            let mut projectiles = self.projectiles.borrow_mut();
+            for proj in del_projs {
+                projectiles.remove(proj);
+            }
+        }
+
+        // After the impacts, whose indices refer to the enemies before the split
+        for enemy in split {
+            self.spawn_split(y, &enemy, events);
+        }
+    }
+
+    fn process_enemies(&mut self, y: usize, events: &mut Vec<Event>) {
+        let mut attack_buf = Vec::new();
+        let mut switching = Vec::new();
+        let mut shots = Vec::new();
+        let mut burnt = Vec::new();
+        self.enemies
+            .borrow_mut()
+            .iter_mut()
+            .enumerate()
+            .for_each(|(enemy_index, enemy)| {
+                let speed_factor = self.speed_factor(enemy.hitbox().start());
+                // ENEMY WAIT
+                let burn = enemy.wait();
+                if burn > 0 {
+                    events.push(Event::Burned {
+                        line: y,
+                        damage: burn,
+                        x: enemy.x(),
+                    });
+                }
+                if enemy.is_dead() {
+                    burnt.push(enemy_index);
+                    return;
+                }
+                let blocking = self
+                    .cells
+                    .iter()
+                    .enumerate()
+                    .find_map(|(turret_index, turret)| {
+                        turret
+                            .as_ref()
+                            .filter(|turret| turret.collide(enemy))
+                            .map(|turret| (turret_index, turret))
+                    });
+                if let Some((turret_index, turret)) = blocking {
+                    if turret.is_wall() && enemy.can_switch_lane() {
+                        switching.push(enemy_index);
+                    } else if !enemy.jump(turret.hitbox()) {
+                        attack_buf.push((enemy_index, turret_index));
+                    }
+                } else if enemy
+                    .range()
+                    .zip(self.turret_distance(enemy.hitbox().start()))
+                    .is_some_and(|(range, distance)| distance <= range)
+                {
+                    if let Some(projectile) = enemy.shoot() {
+                        events.push(Event::ProjectileFired {
+                            line: y,
+                            level: projectile.level(),
+                            owner: Owner::Enemy,
+                        });
+                        shots.push(projectile);
+                    }
+                } else {
+                    enemy.deplace(speed_factor);
+                    if enemy.x() < LANE_END {
+                        events.push(Event::LaneBreached {
+                            line: y,
+                            level: enemy.level(),
+                        });
+                    }
+                }
+            });
+        let mut dead_turrets = Vec::new();
+        for (enemy_index, turret_index) in attack_buf {
+            let mut bren = self.enemies.borrow_mut();
+            let enemy = bren.get_mut(enemy_index).unwrap();
+            if enemy.can_attack() {
+                let turret = self.cells.get_mut(turret_index).unwrap().as_mut().unwrap();
+                turret.take_damage(enemy.attack());
+                if turret.is_dead() {
+                    dead_turrets.push(turret_index);
+                }
+            }
+        }
+        for dead_index in dead_turrets {
+            if let Some(turret) = self.cells[dead_index].take() {
+                events.push(Event::TurretDestroyed {
+                    line: y,
+                    x: dead_index,
+                    level: turret.level(),
+                });
+            }
+        }
+        // An enemy is in one of the lists at most
+        let mut removed = burnt
+            .into_iter()
+            .map(|index| (index, true))
+            .chain(switching.into_iter().map(|index| (index, false)))
+            .collect::<Vec<(usize, bool)>>();
+        removed.sort_unstable_by(|(index1, _), (index2, _)| index2.cmp(index1));
+        let mut split = Vec::new();
+        for (index, dead) in removed {
+            let enemy = self.remove_enemy(index);
+            if dead {
+                events.push(Event::EnemyKilled {
+                    line: y,
+                    level: enemy.level(),
+                    reward: enemy.reward(),
+                    by: Killer::Turret,
+                });
+                split.push(enemy);
+            } else {
+                self.leaving.push(enemy);
+            }
+        }
+        for enemy in split {
+            self.spawn_split(y, &enemy, events);
+        }
+        self.spawn_projectiles(shots);
+
+        self.process_heals();
+        self.process_phases(y, events);
+    }
+
+    /// The bosses whose life fell enough enter their next phase.
+    fn process_phases(&mut self, y: usize, events: &mut Vec<Event>) {
+        let mut minions = Vec::new();
+        for enemy in self.enemies.borrow_mut().iter_mut() {
+            while let Some(effect) = enemy.next_phase() {
+                events.push(Event::BossPhase {
+                    line: y,
+                    level: enemy.level(),
+                    phase: enemy.phase(),
+                });
+                match effect {
+                    PhaseEffect::Minions { level, count } => {
+                        minions.extend(enemy.minions(level, count))
+                    }
+                    PhaseEffect::Stun(duration) => {
+                        for turret in self.cells.iter_mut().flatten() {
+                            turret.apply_status(Status::from_secs(Effect::Stun, duration))
+                        }
+                    }
+                    PhaseEffect::SpeedUp(_) => (),
+                }
+            }
+        }
+        for minion in minions {
+            events.push(Event::EnemySpawned {
+                line: y,
+                level: minion.level(),
+            });
+            self.enemies.borrow_mut().push(minion);
+        }
+    }
+
+    /// The healers give life to the other enemies around them.
+    fn process_heals(&mut self) {
+        let mut enemies = self.enemies.borrow_mut();
+        let heals = enemies
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(idx, enemy)| {
+                let (life, range) = enemy.heal_ready()?;
+                Some((idx, enemy.x(), life, range))
+            })
+            .collect::<Vec<_>>();
+        for (healer, x, life, range) in heals {
+            for (idx, enemy) in enemies.iter_mut().enumerate() {
+                if idx != healer && (enemy.x() - x).abs() <= range {
+                    enemy.heal(life)
+                }
+            }
+        }
+    }
+
+    fn process_turrets(&mut self, y: usize, events: &mut Vec<Event>) {
+        let mut shoots_buf = Vec::with_capacity(self.cells.len());
+        let enemies = self.enemies.borrow();
+        let visible_end = self.board.visible_end();
+        let damage_factors = (0..self.cells.len())
+            .map(|x| self.damage_factor(x))
+            .collect::<Vec<f32>>();
+        let mut burnt = Vec::new();
+        self.cells
+            .iter_mut()
+            .zip(damage_factors)
+            .enumerate()
+            .for_each(|(x, (turret, damage_factor))| {
+                if let Some(turret) = turret {
+                    // TURRET WAIT
+                    turret.wait();
+                    if turret.is_dead() {
+                        burnt.push(x);
+                    } else if let Some(target) = (turret.reach().is_none() && turret.can_attack())
+                        .then(|| aim(turret, &enemies, visible_end))
+                        .flatten()
+                    {
+                        let mut projectile = turret.shoot_at(target, visible_end).unwrap();
+                        projectile.amplify(damage_factor);
+                        events.push(Event::ProjectileFired {
+                            line: y,
+                            level: projectile.level(),
+                            owner: Owner::Turret,
+                        });
+                        shoots_buf.push(projectile)
+                    }
+                }
+            });
+        drop(enemies);
+        for x in burnt {
+            if let Some(turret) = self.cells[x].take() {
+                events.push(Event::TurretDestroyed {
+                    line: y,
+                    x,
+                    level: turret.level(),
+                });
+            }
+        }
+        self.spawn_projectiles(shoots_buf);
+    }
+
+    fn spawn_new_enemies(&mut self, y: usize, events: &mut Vec<Event>) {
+        if let Some(wave) = &self.current_wave {
+            if let Some(enemy) = wave.borrow_mut().next() {
+                events.push(Event::EnemySpawned {
+                    line: y,
+                    level: enemy.level(),
+                });
+                self.enemies.borrow_mut().push(enemy);
+            }
+        }
+    }
+}
+
+/// The enemy aimed by `turret` according to its priority, among the ones in its range.
+fn aim(turret: &Turret, enemies: &[Enemy], visible_end: f32) -> Option<usize> {
+    let range = turret.range(visible_end);
+    let in_range = enemies
+        .iter()
+        .enumerate()
+        .filter(|(_, enemy)| range.contains(enemy.hitbox().end()));
+    let by = |value: fn(&Enemy) -> f32| {
+        move |(_, enmy1): &(usize, &Enemy), (_, enmy2): &(usize, &Enemy)| {
+            value(enmy1).partial_cmp(&value(enmy2)).unwrap()
+        }
+    };
+    match turret.priority() {
+        Priority::First => in_range.min_by(by(|enemy| enemy.hitbox().end())),
+        Priority::Strongest => in_range.max_by_key(|(_, enemy)| enemy.life()),
+        // The speeds of the enemies are negative
+        Priority::Fastest => in_range.min_by(by(Enemy::speed)),
+    }
+    .map(|(idx, _)| idx)
+}
+
+/// The enemies hit by `projectile` when it reaches the enemy `target`, with their damage.
+fn impacts(
+    projectile: &Projectile,
+    target: usize,
+    enemies: &[Enemy],
+    is_dead: impl Fn(usize) -> bool,
+) -> Vec<(usize, u32)> {
+    let damage = projectile.damage();
+    let mut hits = vec![(target, damage)];
+    match projectile.behaviour() {
+        Some(Behaviour::Splash { radius, factor }) => {
+            let x = enemies[target].x();
+            hits.extend(
+                enemies
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, enemy)| {
+                        *idx != target && !is_dead(*idx) && (enemy.x() - x).abs() <= radius
+                    })
+                    .map(|(idx, _)| (idx, (damage as f32 * factor) as u32)),
+            );
+        }
+        Some(Behaviour::Chain {
+            count,
+            range,
+            factor,
+        }) => {
+            let mut damage = damage as f32;
+            let mut x = enemies[target].x();
+            for _ in 0..count {
+                damage *= factor;
+                let next = enemies
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| !is_dead(*idx) && !hits.iter().any(|(hit, _)| hit == idx))
+                    .map(|(idx, enemy)| (idx, (enemy.x() - x).abs()))
+                    .filter(|(_, distance)| *distance <= range)
+                    .min_by(|(_, d1), (_, d2)| d1.partial_cmp(d2).unwrap());
+                match next {
+                    Some((idx, _)) => {
+                        x = enemies[idx].x();
+                        hits.push((idx, damage as u32));
+                    }
+                    None => break,
+                }
+            }
+        }
+        Some(Behaviour::Pierce(_)) | None => (),
+    }
+    hits
+}
+
+impl Default for Line {
+    fn default() -> Self {
+        Self::new(BoardSize::default())
+    }
+}
+
+#[test]
+fn hits_are_reported() {
+    let mut line = Line::default();
+    line.enemies.borrow_mut().push(Enemy::prefab(2).unwrap());
+    let projectile = Projectile::new_turret_projectile(1, 20.).unwrap();
+    let damage = projectile.damage();
+
+    let mut events = Vec::new();
+    for frame in 0..10 * crate::FPS {
+        if frame == 2 * crate::FPS {
+            line.spawn_projectile(projectile.clone());
+        }
+        line.process(3, &mut events);
+    }
+    assert!(matches!(
+        events.as_slice(),
+        [Event::ProjectileHit { line: 3, level: 1, damage: d, .. }] if *d == damage
+    ));
+}
+
+#[test]
+fn enemy_abilities() {
+    use super::enemy::Ability;
+
+    let mut events = Vec::new();
+
+    // The shield absorbs the first damage
+    let mut shielded = Enemy::prefab(9).unwrap();
+    assert_eq!(shielded.take_damage(100), 0);
+    assert_eq!(shielded.take_damage(100), 80);
+    assert!(shielded.is_dead());
+
+    // The splitter leaves smaller enemies behind it
+    let mut line = Line::default();
+    line.enemies.borrow_mut().push(Enemy::prefab(12).unwrap());
+    line.use_god(0, &mut events);
+    line.use_god(0, &mut events);
+    assert_eq!(line.enemies.borrow().len(), 3);
+    assert!(line.enemies.borrow().iter().all(|enemy| enemy.level() == 3));
+
+    // The healer heals the enemies around it
+    let mut line = Line::default();
+    let mut hurt = Enemy::prefab(2).unwrap();
+    hurt.take_damage(100);
+    line.enemies.borrow_mut().push(hurt);
+    line.enemies.borrow_mut().push(Enemy::prefab(10).unwrap());
+    for _ in 0..crate::FPS {
+        line.process(0, &mut events);
+    }
+    assert!(line.enemies.borrow()[0].health() > 150. / 250.);
+
+    // The jumper leaps over the first turret only
+    let mut line = Line::default();
+    line.add_turret(6, Turret::prefab_turret(3).unwrap());
+    line.add_turret(5, Turret::prefab_turret(3).unwrap());
+    let jumper = Enemy::prefab(11).unwrap();
+    assert_eq!(jumper.ability(), Some(Ability::Jumper));
+    line.enemies.borrow_mut().push(jumper);
+    for _ in 0..5 * crate::FPS {
+        line.process(0, &mut events);
+    }
+    let x = line.enemies.borrow()[0].hitbox().start();
+    let board = BoardSize::default();
+    assert!(x > board.turret_x(5) && x < board.turret_x(6));
+}
+
+#[test]
+fn enemies_shoot_turrets() {
+    let mut line = Line::default();
+    line.add_turret(2, Turret::prefab_turret(3).unwrap());
+    let ranged = Enemy::prefab(14).unwrap();
+    let range = ranged.range().unwrap();
+    line.enemies.borrow_mut().push(ranged);
+
+    let mut events = Vec::new();
+    for _ in 0..40 * crate::FPS {
+        line.process(0, &mut events);
+    }
+    let turret = line.cells[2].as_ref().unwrap();
+    assert!(turret.health() < 1.);
+    assert!(line.enemies.borrow()[0].hitbox().start() - turret.hitbox().end() <= range);
+    assert!(events.iter().any(|event| matches!(
+        event,
+        Event::ProjectileFired {
+            owner: Owner::Enemy,
+            ..
+        }
+    )));
+    assert!(events
+        .iter()
+        .all(|event| !matches!(event, Event::EnemyKilled { .. })));
+}
+
+#[test]
+fn boss_phases() {
+    let mut line = Line::default();
+    line.add_turret(2, Turret::prefab_turret(1).unwrap());
+    let boss = Enemy::prefab(16).unwrap();
+    assert!(boss.is_boss());
+    line.enemies.borrow_mut().push(boss);
+
+    let mut events = Vec::new();
+    line.enemies.borrow_mut()[0].take_damage(1500);
+    line.process(0, &mut events);
+    assert!(!line.cells[2].as_ref().unwrap().statuses().can_attack());
+
+    // Two phases at once
+    line.enemies.borrow_mut()[0].take_damage(2500);
+    line.process(0, &mut events);
+    assert_eq!(line.enemies.borrow().len(), 3);
+    let phases = events
+        .iter()
+        .filter_map(|event| match event {
+            Event::BossPhase { phase, .. } => Some(*phase),
+            _ => None,
+        })
+        .collect::<Vec<usize>>();
+    assert_eq!(phases, vec![1, 2, 3]);
+}
+
+#[test]
+fn statuses_on_hit() {
+    let mut line = Line::default();
+    line.enemies.borrow_mut().push(Enemy::prefab(2).unwrap());
+    line.spawn_projectile(Projectile::new_turret_projectile(6, 20.).unwrap());
+
+    let mut events = Vec::new();
+    while line.projectiles.borrow().len() == 1 {
+        line.process(0, &mut events);
+    }
+    assert_eq!(line.enemies.borrow()[0].statuses().speed_factor(), 0.5);
+
+    // The burns kill on their own
+    let mut burning = Enemy::prefab(3).unwrap();
+    burning.apply_status(Status::from_secs(Effect::Burn(50), 1.));
+    line.enemies.borrow_mut().push(burning);
+    for _ in 0..crate::FPS {
+        line.process(0, &mut events);
+    }
+    assert_eq!(line.enemies.borrow().len(), 1);
+    assert!(events.iter().any(|event| matches!(
+        event,
+        Event::EnemyKilled {
+            level: 3,
+            by: Killer::Turret,
+            ..
+        }
+    )));
+}
+
+#[test]
+fn projectile_behaviours() {
+    let group = |count: usize| {
+        let line = Line::default();
+        for i in 0..count {
+            let mut enemy = Enemy::prefab(2).unwrap();
+            enemy.deplace(-(i as f32) * 3. / enemy.speed());
+            line.enemies.borrow_mut().push(enemy);
+        }
+        line
+    };
+    let damaged = |line: &Line| {
+        line.enemies
+            .borrow()
+            .iter()
+            .filter(|enemy| enemy.health() < 1.)
+            .count()
+    };
+    let mut events = Vec::new();
+
+    // The splash hits the enemies close to the first one, the chain jumps three times
+    for (level, hit) in [(8, 3), (9, 4)] {
+        let mut line = group(5);
+        line.spawn_projectile(Projectile::new_turret_projectile(level, 20.).unwrap());
+        while !line.projectiles.borrow().is_empty() {
+            line.process(0, &mut events);
+        }
+        assert_eq!(damaged(&line), hit);
+    }
+
+    // The piercing projectile goes through three enemies, the other one hits the first of them
+    let mut line = group(5);
+    line.spawn_projectile(Projectile::new_turret_projectile(7, 20.).unwrap());
+    line.spawn_projectile(Projectile::new_turret_projectile(1, 20.).unwrap());
+    while !line.projectiles.borrow().is_empty() {
+        line.process(0, &mut events);
+    }
+    assert_eq!(damaged(&line), 3);
+
+    // Two projectiles reaching the same enemy in the same frame, the first one kills it
+    let mut line = Line::default();
+    line.enemies.borrow_mut().push(Enemy::prefab(3).unwrap());
+    for _ in 0..2 {
+        line.spawn_projectile(Projectile::new_turret_projectile(2, 20.).unwrap());
+    }
+    let mut events = Vec::new();
+    for _ in 0..10 * crate::FPS {
+        line.process(0, &mut events);
+    }
+    let count = |kind: fn(&Event) -> bool| events.iter().filter(|event| kind(event)).count();
+    assert_eq!(
+        count(|event| matches!(event, Event::ProjectileHit { .. })),
+        1
+    );
+    assert_eq!(count(|event| matches!(event, Event::EnemyKilled { .. })), 1);
+}
+
+#[test]
+fn turret_range_and_priority() {
+    let at = |level: u8, x: f32| {
+        let mut enemy = Enemy::prefab(level).unwrap();
+        enemy.deplace((x - enemy.x()) / enemy.speed());
+        enemy
+    };
+    let turret = |level: u8| Turret::prefab_turret(level).unwrap().set_x(10.);
+    let enemies = [at(1, 20.), at(4, 60.), at(3, 70.)];
+
+    assert_eq!(aim(&turret(14), &enemies, BOARD_LENGHT), Some(0));
+    assert_eq!(aim(&turret(14), &enemies[1..], BOARD_LENGHT), None);
+    assert_eq!(aim(&turret(15), &enemies, BOARD_LENGHT), Some(1));
+    assert_eq!(aim(&turret(7), &enemies, BOARD_LENGHT), Some(2));
+    assert_eq!(aim(&turret(1), &enemies, BOARD_LENGHT), Some(0));
+
+    // The sniper shoots over the close enemy
+    let mut line = Line::default();
+    line.add_turret(0, Turret::prefab_turret(15).unwrap());
+    let x = line.board.turret_x(0);
+    line.enemies
+        .borrow_mut()
+        .extend([at(1, x + 20.), at(4, x + 60.)]);
+    let mut events = Vec::new();
+    while events.is_empty() {
+        line.process(0, &mut events);
+        events.retain(|event| matches!(event, Event::ProjectileHit { .. }));
+    }
+    let enemies = line.enemies.borrow();
+    assert_eq!(enemies[0].health(), 1.);
+    assert!(enemies[1].health() < 1.);
+}
@@ -1,16 +1,16 @@
 use std::rc::Rc;
 
+use serde::{Deserialize, Serialize};
+
 use super::projectile::Projectile;
-use super::NBR_OF_LINE;
-use crate::log;
 use crate::FPS;
 
 const PLAYER_MAX_LEVEL: u8 = 6;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Player {
-    pub(crate) level: u8,
-    pub(crate) line: usize,
+    pub level: u8,
+    pub line: usize,
     shooting_speed: u64,
     waiting: u64,
     upgrade_cost_text: Rc<String>,
@@ -67,8 +67,8 @@ impl Player {
         }
     }
 
-    pub fn down(&mut self) {
-        if self.line < NBR_OF_LINE - 1 {
+    pub fn down(&mut self, lines: usize) {
+        if self.line + 1 < lines {
             self.line += 1;
         }
     }
@@ -0,0 +1,246 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::FPS;
+
+use super::components::RangeBox;
+use super::status::Status;
+use super::units::units;
+
+/// Who fired a projectile: the ones of the enemies fly leftward and hit the turrets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Owner {
+    Player,
+    Turret,
+    Enemy,
+}
+
+/// What a projectile does once it hits an enemy, it vanishes by default.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Behaviour {
+    /// Go through the enemies, until `count` of them are hit
+    Pierce(u8),
+    /// Also hit the enemies closer than `radius` to the enemy hit, with `factor` of the damage
+    Splash { radius: f32, factor: f32 },
+    /// Jump up to `count` times to the nearest enemy within `range`, with `factor` of the
+    /// previous damage each time
+    Chain { count: u8, range: f32, factor: f32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Projectile {
+    x: f32,
+    damage: u32,
+    level: u8,
+    speed: f32,
+    hitbox: RangeBox,
+    owner: Owner,
+    /// Applied to the unit hit
+    status: Option<Status>,
+    behaviour: Option<Behaviour>,
+    /// Number of enemies hit
+    hits: u8,
+    sprite: u8,
+    /// The only enemy which can be hit, until the first hit
+    target: Option<usize>,
+    /// Positions where the projectile can hit
+    range: Option<RangeBox>,
+    next_impact: Option<usize>,
+}
+
+impl Projectile {
+    pub fn new(x: f32, damage: u32, level: u8, speed: f32, hitbox: RangeBox, owner: Owner) -> Self {
+        Self {
+            x,
+            damage,
+            level,
+            speed,
+            hitbox,
+            owner,
+            status: None,
+            behaviour: None,
+            hits: 0,
+            sprite: level,
+            target: None,
+            range: None,
+            next_impact: None,
+        }
+    }
+
+    #[inline]
+    pub fn with_status(mut self, status: Option<Status>) -> Self {
+        self.status = status;
+        self
+    }
+
+    #[inline]
+    pub fn status(&self) -> Option<Status> {
+        self.status
+    }
+
+    #[inline]
+    pub fn with_behaviour(mut self, behaviour: Option<Behaviour>) -> Self {
+        self.behaviour = behaviour;
+        self
+    }
+
+    #[inline]
+    pub fn behaviour(&self) -> Option<Behaviour> {
+        self.behaviour
+    }
+
+    /// Draw the projectile with the sprite of the level `sprite`.
+    #[inline]
+    pub fn with_sprite(mut self, sprite: u8) -> Self {
+        self.sprite = sprite;
+        self
+    }
+
+    #[inline]
+    pub fn sprite(&self) -> u8 {
+        self.sprite
+    }
+
+    #[inline]
+    pub fn with_target(mut self, target: Option<usize>, range: Option<RangeBox>) -> Self {
+        self.target = target;
+        self.range = range;
+        self
+    }
+
+    /// Whether the enemy at `id`, ending at `enemy_end`, can be hit.
+    #[inline]
+    pub fn can_hit(&self, id: usize, enemy_end: f32) -> bool {
+        self.target.is_none_or(|target| target == id)
+            && self.range.is_none_or(|range| range.contains(enemy_end))
+    }
+
+    /// Whether the projectile has gone past its range.
+    #[inline]
+    pub fn is_out_of_range(&self) -> bool {
+        self.range.is_some_and(|range| self.x > range.end())
+    }
+
+    /// Count a hit on the enemy ending at `enemy_end`, return true if the projectile goes through.
+    pub fn pierce(&mut self, enemy_end: f32) -> bool {
+        self.hits = self.hits.saturating_add(1);
+        self.target = None;
+        match self.behaviour {
+            Some(Behaviour::Pierce(count)) if self.hits < count => {
+                // Past the enemy, so that it is not hit twice
+                self.x = enemy_end - self.hitbox.start() + 0.1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[inline]
+    pub fn x(&self) -> f32 {
+        self.x
+    }
+
+    #[inline]
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    #[inline]
+    pub fn damage(&self) -> u32 {
+        self.damage
+    }
+
+    #[inline]
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    #[inline]
+    pub fn owner(&self) -> Owner {
+        self.owner
+    }
+
+    #[inline]
+    pub fn take_next_impact(&mut self) -> Option<usize> {
+        self.next_impact.take()
+    }
+
+    #[inline]
+    pub fn add_next_impact(&mut self, id: usize) {
+        self.next_impact = Some(id)
+    }
+
+    /// Keep the next impact and the target right once the enemy at `id` has been removed from
+    /// the line, a projectile losing its target hits the next enemy met.
+    #[inline]
+    pub fn enemy_removed(&mut self, id: usize) {
+        let shift = |index: Option<usize>| match index {
+            Some(index) if index == id => None,
+            Some(index) if index > id => Some(index - 1),
+            index => index,
+        };
+        self.next_impact = shift(self.next_impact);
+        self.target = shift(self.target);
+    }
+
+    pub fn new_player_projectile(level: u8) -> Self {
+        Self::new(
+            4.,
+            (level as u32).pow(2) * 10 + 10,
+            level,
+            (level as f32 / 2. + 0.8) * 30. / FPS as f32,
+            RangeBox::new(-1., 2.),
+            Owner::Player,
+        )
+    }
+
+    #[inline]
+    pub fn new_turret_projectile(level: u8, x: f32) -> Option<Self> {
+        units()
+            .projectile(level)
+            .map(|def| def.build(x, Owner::Turret))
+    }
+
+    #[inline]
+    pub fn new_enemy_projectile(level: u8, x: f32) -> Option<Self> {
+        units()
+            .projectile(level)
+            .map(|def| def.build(x, Owner::Enemy))
+    }
+
+    #[inline]
+    pub fn amplify(&mut self, factor: f32) {
+        self.damage = (self.damage as f32 * factor) as u32
+    }
+
+    #[inline]
+    pub fn deplace(&mut self) {
+        self.x += self.speed;
+    }
+
+    #[inline]
+    pub fn hitbox(&self) -> RangeBox {
+        self.hitbox + self.x
+    }
+}
+
+impl PartialEq for Projectile {
+    fn eq(&self, other: &Self) -> bool {
+        self.x == other.x
+    }
+}
+
+impl Eq for Projectile {}
+
+impl PartialOrd for Projectile {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Projectile {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.x.partial_cmp(&other.x).unwrap()
+    }
+}
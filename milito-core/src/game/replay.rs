@@ -0,0 +1,248 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::Game;
+
+/// Every action of the player which have an effect on the simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Command {
+    MovePlayerUp,
+    MovePlayerDown,
+    PlayerShoot,
+    PlaceTurret { level: u8, x: usize, y: usize },
+    DeleteTurret { x: usize, y: usize },
+    UpgradeTurret { x: usize, y: usize },
+    RepairTurret { x: usize, y: usize },
+    UpgradePlayer,
+    UseGod,
+    StartNextWave,
+    SkipWave,
+    MoreWaves(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Input {
+    /// Number of `Game::process` done before the command
+    pub frame: u64,
+    pub command: Command,
+}
+
+/// The seed of a game and all the commands played on it, which is enough to reproduce the game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputLog {
+    seed: u64,
+    inputs: Vec<Input>,
+}
+
+impl InputLog {
+    #[inline]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            inputs: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    #[inline]
+    pub fn inputs(&self) -> &[Input] {
+        &self.inputs
+    }
+
+    #[inline]
+    pub fn push(&mut self, frame: u64, command: Command) {
+        self.inputs.push(Input { frame, command })
+    }
+}
+
+// Format: `seed;frame:command;frame:command;...`
+// It only uses characters which can be put in an url.
+impl fmt::Display for InputLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.seed)?;
+        for input in self.inputs.iter() {
+            write!(f, ";{}:{}", input.frame, input.command)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MovePlayerUp => write!(f, "U"),
+            Self::MovePlayerDown => write!(f, "D"),
+            Self::PlayerShoot => write!(f, "S"),
+            Self::PlaceTurret { level, x, y } => write!(f, "T{}.{}.{}", level, x, y),
+            Self::DeleteTurret { x, y } => write!(f, "X{}.{}", x, y),
+            Self::UpgradeTurret { x, y } => write!(f, "A{}.{}", x, y),
+            Self::RepairTurret { x, y } => write!(f, "R{}.{}", x, y),
+            Self::UpgradePlayer => write!(f, "P"),
+            Self::UseGod => write!(f, "G"),
+            Self::StartNextWave => write!(f, "N"),
+            Self::SkipWave => write!(f, "K"),
+            Self::MoreWaves(amount) => write!(f, "M{}", amount),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseInputLogError {
+    /// Index of the faulty entry, the seed is the entry 0
+    pub entry: usize,
+    pub text: String,
+}
+
+impl fmt::Display for ParseInputLogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid replay entry {}: \"{}\"", self.entry, self.text)
+    }
+}
+
+impl std::error::Error for ParseInputLogError {}
+
+impl FromStr for InputLog {
+    type Err = ParseInputLogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut entries = s.trim().split(';');
+        let error = |entry: usize, text: &str| ParseInputLogError {
+            entry,
+            text: text.to_owned(),
+        };
+
+        let seed = entries.next().unwrap_or_default();
+        let mut log = Self::new(seed.parse().map_err(|_| error(0, seed))?);
+
+        for (i, entry) in entries.enumerate() {
+            let input = entry
+                .split_once(':')
+                .and_then(|(frame, command)| Some((frame.parse().ok()?, command.parse().ok()?)))
+                .ok_or_else(|| error(i + 1, entry))?;
+            log.push(input.0, input.1);
+        }
+
+        Ok(log)
+    }
+}
+
+impl FromStr for Command {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let kind = chars.next().ok_or(())?;
+        let args = chars
+            .as_str()
+            .split('.')
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| arg.parse::<usize>().map_err(|_| ()))
+            .collect::<Result<Vec<usize>, ()>>()?;
+
+        match (kind, args.as_slice()) {
+            ('U', []) => Ok(Self::MovePlayerUp),
+            ('D', []) => Ok(Self::MovePlayerDown),
+            ('S', []) => Ok(Self::PlayerShoot),
+            ('T', &[level, x, y]) if level <= u8::MAX as usize => Ok(Self::PlaceTurret {
+                level: level as u8,
+                x,
+                y,
+            }),
+            ('X', &[x, y]) => Ok(Self::DeleteTurret { x, y }),
+            ('A', &[x, y]) => Ok(Self::UpgradeTurret { x, y }),
+            ('R', &[x, y]) => Ok(Self::RepairTurret { x, y }),
+            ('P', []) => Ok(Self::UpgradePlayer),
+            ('G', []) => Ok(Self::UseGod),
+            ('N', []) => Ok(Self::StartNextWave),
+            ('K', []) => Ok(Self::SkipWave),
+            ('M', &[amount]) if amount <= u32::MAX as usize => Ok(Self::MoreWaves(amount as u32)),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Play back an `InputLog` on a game created with the same seed.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    log: InputLog,
+    cursor: usize,
+}
+
+impl Replay {
+    #[inline]
+    pub fn new(log: InputLog) -> Self {
+        Self { log, cursor: 0 }
+    }
+
+    #[inline]
+    pub fn is_ended(&self) -> bool {
+        self.cursor >= self.log.inputs.len()
+    }
+
+    /// Apply the commands of the current frame, then process the frame.
+    pub fn tick(&mut self, game: &mut Game) {
+        while let Some(input) = self.log.inputs.get(self.cursor) {
+            if input.frame > game.frame() {
+                break;
+            }
+            game.apply(input.command);
+            self.cursor += 1;
+        }
+        game.process();
+    }
+}
+
+#[test]
+fn replay_reproduces_the_game() {
+    use super::FPS;
+
+    let mut game = Game::standard(7);
+    let turret = |level, x, y| Command::PlaceTurret { level, x, y };
+    let mut script = vec![
+        (0, turret(1, 0, 0)),
+        (0, turret(1, 0, 1)),
+        (0, Command::StartNextWave),
+        (20, Command::MovePlayerDown),
+        (25, Command::PlayerShoot),
+        (300, turret(2, 1, 2)),
+        (500, Command::DeleteTurret { x: 0, y: 1 }),
+        (900, Command::UseGod),
+        (1200, Command::StartNextWave),
+    ];
+    script.reverse();
+
+    for _ in 0..60 * FPS {
+        while script.last().map(|(frame, _)| *frame) == Some(game.frame()) {
+            game.apply(script.pop().unwrap().1);
+        }
+        game.process();
+    }
+
+    let log = game.input_log().to_string().parse::<InputLog>().unwrap();
+    assert_eq!(&log, game.input_log());
+
+    let mut replayed = Game::standard(log.seed());
+    let mut replay = Replay::new(log);
+    for _ in 0..60 * FPS {
+        replay.tick(&mut replayed);
+    }
+
+    assert!(replay.is_ended());
+    assert_eq!(replayed.frame(), game.frame());
+    assert_eq!(replayed.money, game.money);
+    assert_eq!(replayed.stats, game.stats);
+    assert_eq!(replayed.input_log(), game.input_log());
+}
+
+#[test]
+fn parse_error_points_to_the_entry() {
+    let error = "12;0:N;4:T1.2".parse::<InputLog>().unwrap_err();
+    assert_eq!(error.entry, 2);
+    assert!("seed;0:N".parse::<InputLog>().is_err());
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::Game;
+
+/// Must be bumped each time the serialized shape of `Game` changes.
+pub const SAVE_VERSION: u32 = 18;
+
+#[derive(Debug)]
+pub enum LoadError {
+    Version { found: u32, expected: u32 },
+    Invalid(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Version { found, expected } => write!(
+                f,
+                "the save has the version {} but the version {} is expected",
+                found, expected
+            ),
+            Self::Invalid(err) => write!(f, "the save is corrupted: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+#[derive(Serialize)]
+struct SaveRef<'a> {
+    version: u32,
+    game: &'a Game,
+}
+
+#[derive(Deserialize)]
+struct Save {
+    game: Game,
+}
+
+impl Game {
+    pub fn save(&self) -> String {
+        serde_json::to_string(&SaveRef {
+            version: SAVE_VERSION,
+            game: self,
+        })
+        .unwrap()
+    }
+
+    pub fn load(save: &str) -> Result<Self, LoadError> {
+        // The version is read first, so an old save is not mistaken for a corrupted one
+        let header = serde_json::from_str::<SaveHeader>(save)
+            .map_err(|err| LoadError::Invalid(err.to_string()))?;
+        if header.version != SAVE_VERSION {
+            return Err(LoadError::Version {
+                found: header.version,
+                expected: SAVE_VERSION,
+            });
+        }
+
+        serde_json::from_str::<Save>(save)
+            .map(|save| save.game)
+            .map_err(|err| LoadError::Invalid(err.to_string()))
+    }
+}
+
+#[test]
+fn load_a_saved_game() {
+    use super::{replay::Command, FPS};
+
+    let mut game = Game::standard(3);
+    game.place_turret(1, 2, 4);
+    game.start_next_wave();
+    for _ in 0..20 * FPS {
+        game.process();
+    }
+    game.apply(Command::PlayerShoot);
+
+    // The HashMaps of the waves are not serialized in a stable order
+    let state = |game: &Game| {
+        let enemies = game
+            .lines
+            .iter()
+            .map(|line| line.enemies.borrow().iter().map(|e| e.x()).collect())
+            .collect::<Vec<Vec<f32>>>();
+        (
+            game.money,
+            game.frame(),
+            game.stats.clone(),
+            game.god,
+            enemies,
+        )
+    };
+
+    let mut loaded = Game::load(&game.save()).unwrap();
+    assert_eq!(state(&loaded), state(&game));
+    assert_eq!(loaded.input_log(), game.input_log());
+
+    for _ in 0..60 * FPS {
+        game.process();
+        loaded.process();
+    }
+    assert_eq!(state(&loaded), state(&game));
+
+    let old = game
+        .save()
+        .replacen(&format!("\"version\":{}", SAVE_VERSION), "\"version\":0", 1);
+    assert!(matches!(
+        Game::load(&old),
+        Err(LoadError::Version { found: 0, .. })
+    ));
+    assert!(matches!(Game::load("{}"), Err(LoadError::Invalid(_))));
+}
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+
+/// Number of runs kept by a leaderboard.
+pub const LEADERBOARD_SIZE: usize = 10;
+
+pub const WAVE_POINTS: u32 = 1000;
+pub const KILL_POINTS: u32 = 10;
+
+/// The score of a run: every wave cleared, enemy killed and coin earned is worth points.
+#[inline]
+pub fn score(waves_cleared: usize, kills: u32, earned: u32) -> u32 {
+    waves_cleared as u32 * WAVE_POINTS + kills * KILL_POINTS + earned
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Run {
+    pub score: u32,
+    /// The wave during which the run ended
+    pub wave: usize,
+    pub seed: u64,
+    /// As `YYYY-MM-DD`
+    pub date: String,
+}
+
+/// The best runs, from the best to the worst.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    runs: Vec<Run>,
+}
+
+impl Leaderboard {
+    /// An unreadable leaderboard is replaced by an empty one.
+    pub fn from_json(text: &str) -> Self {
+        serde_json::from_str(text).unwrap_or_default()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    #[inline]
+    pub fn runs(&self) -> &[Run] {
+        &self.runs
+    }
+
+    /// Add the run and return its rank, `None` if it is not good enough to be kept.
+    pub fn insert(&mut self, run: Run) -> Option<usize> {
+        // The older runs stay first in case of equality
+        let rank = self
+            .runs
+            .iter()
+            .position(|other| other.score < run.score)
+            .unwrap_or(self.runs.len());
+        if rank >= LEADERBOARD_SIZE {
+            return None;
+        }
+        self.runs.insert(rank, run);
+        self.runs.truncate(LEADERBOARD_SIZE);
+        Some(rank)
+    }
+}
+
+/// The results of the daily challenges, a single attempt is allowed each day.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DailyHistory {
+    runs: Vec<Run>,
+}
+
+impl DailyHistory {
+    /// An unreadable history is replaced by an empty one.
+    pub fn from_json(text: &str) -> Self {
+        serde_json::from_str(text).unwrap_or_default()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    /// The results from the most recent one.
+    #[inline]
+    pub fn runs(&self) -> &[Run] {
+        &self.runs
+    }
+
+    #[inline]
+    pub fn get(&self, date: &str) -> Option<&Run> {
+        self.runs.iter().find(|run| run.date == date)
+    }
+
+    /// Use the attempt of the day, return false if it has already been used.
+    pub fn start(&mut self, date: &str, seed: u64) -> bool {
+        if self.get(date).is_some() {
+            return false;
+        }
+        self.runs.insert(
+            0,
+            Run {
+                score: 0,
+                wave: 0,
+                seed,
+                date: date.to_owned(),
+            },
+        );
+        true
+    }
+
+    /// Update the result of the attempt played with `seed`.
+    pub fn update(&mut self, seed: u64, score: u32, wave: usize) {
+        if let Some(run) = self.runs.iter_mut().find(|run| run.seed == seed) {
+            run.score = score;
+            run.wave = wave;
+        }
+    }
+}
+
+#[test]
+fn keep_the_best_runs() {
+    let run = |score| Run {
+        score,
+        wave: 1,
+        seed: score as u64,
+        date: "2021-07-14".to_owned(),
+    };
+
+    let mut leaderboard = Leaderboard::default();
+    for score in 0..LEADERBOARD_SIZE as u32 {
+        assert_eq!(leaderboard.insert(run(score * 10)), Some(0));
+    }
+    assert_eq!(leaderboard.insert(run(0)), None);
+    assert_eq!(leaderboard.insert(run(45)), Some(5));
+    assert_eq!(leaderboard.runs().len(), LEADERBOARD_SIZE);
+    assert_eq!(leaderboard.runs()[LEADERBOARD_SIZE - 1].score, 10);
+
+    assert_eq!(Leaderboard::from_json(&leaderboard.to_json()), leaderboard);
+    assert_eq!(Leaderboard::from_json("corrupted"), Leaderboard::default());
+}
+
+#[test]
+fn one_daily_attempt() {
+    let mut history = DailyHistory::default();
+    assert!(history.start("2021-07-14", 14));
+    assert!(!history.start("2021-07-14", 14));
+    assert!(history.start("2021-07-15", 15));
+    history.update(14, 2500, 3);
+
+    assert_eq!(history.runs()[0].date, "2021-07-15");
+    assert_eq!(history.get("2021-07-14").map(|run| run.score), Some(2500));
+    assert_eq!(DailyHistory::from_json(&history.to_json()), history);
+
+    use crate::utils::seed_from_date;
+    assert_ne!(seed_from_date("2021-07-14"), seed_from_date("2021-07-15"));
+}
@@ -0,0 +1,206 @@
+use std::mem::discriminant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::FPS;
+
+/// Number of burns which add up on a unit.
+pub const BURN_STACKS: usize = 3;
+
+/// A temporary effect on an enemy or a turret.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Effect {
+    /// Multiply the speed
+    Slow(f32),
+    /// Lose this amount of life every second
+    Burn(u32),
+    /// Neither move nor attack
+    Freeze,
+    /// No attack
+    Stun,
+    /// Multiply the waiting between two attacks
+    AttackSlow(f32),
+}
+
+impl Effect {
+    /// Whether the effect is at least as strong as `other`, which is of the same kind.
+    fn is_stronger(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Slow(factor), Self::Slow(other)) => factor <= other,
+            (Self::Burn(life), Self::Burn(other)) => life >= other,
+            (Self::AttackSlow(factor), Self::AttackSlow(other)) => factor >= other,
+            _ => true,
+        }
+    }
+
+    /// Whether the effect has valid values, for the units.
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Self::Slow(factor) => (0. ..1.).contains(&factor),
+            Self::Burn(life) => life > 0,
+            Self::AttackSlow(factor) => factor > 1.,
+            Self::Freeze | Self::Stun => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Status {
+    pub effect: Effect,
+    /// Frames left
+    pub frames: u32,
+}
+
+impl Status {
+    #[inline]
+    pub fn new(effect: Effect, frames: u32) -> Self {
+        Self { effect, frames }
+    }
+
+    /// A status lasting `duration` seconds.
+    #[inline]
+    pub fn from_secs(effect: Effect, duration: f32) -> Self {
+        Self::new(effect, (duration * FPS as f32) as u32)
+    }
+}
+
+/// A status as written in the units, with its duration in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StatusDef {
+    pub effect: Effect,
+    pub duration: f32,
+}
+
+impl StatusDef {
+    #[inline]
+    pub fn build(&self) -> Status {
+        Status::from_secs(self.effect, self.duration)
+    }
+}
+
+/// The statuses of a unit.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Statuses(Vec<Status>);
+
+impl Statuses {
+    /// Up to `BURN_STACKS` burns add up, then a new burn replaces the weakest one if it is stronger.
+    /// The other effects do not stack: the strongest one is kept, with the longest duration.
+    pub fn apply(&mut self, status: Status) {
+        if status.frames == 0 {
+            return;
+        }
+
+        if let Effect::Burn(_) = status.effect {
+            let is_burn = |current: &&mut Status| matches!(current.effect, Effect::Burn(_));
+            if self.0.iter_mut().filter(is_burn).count() < BURN_STACKS {
+                self.0.push(status);
+            } else if let Some(weakest) =
+                self.0
+                    .iter_mut()
+                    .filter(is_burn)
+                    .min_by_key(|current| match current.effect {
+                        Effect::Burn(life) => life,
+                        _ => 0,
+                    })
+            {
+                if status.effect.is_stronger(&weakest.effect) {
+                    *weakest = status;
+                }
+            }
+            return;
+        }
+
+        let kind = discriminant(&status.effect);
+        match self
+            .0
+            .iter_mut()
+            .find(|current| discriminant(&current.effect) == kind)
+        {
+            Some(current) => {
+                current.frames = current.frames.max(status.frames);
+                if status.effect.is_stronger(&current.effect) {
+                    current.effect = status.effect;
+                }
+            }
+            None => self.0.push(status),
+        }
+    }
+
+    /// Advance of a frame, return the damage of the burns.
+    pub fn tick(&mut self) -> u32 {
+        let mut damage = 0;
+        for status in self.0.iter_mut() {
+            status.frames -= 1;
+            if let Effect::Burn(life) = status.effect {
+                if (status.frames as u64).is_multiple_of(FPS) {
+                    damage += life;
+                }
+            }
+        }
+        self.0.retain(|status| status.frames > 0);
+        damage
+    }
+
+    #[inline]
+    pub fn effects(&self) -> impl Iterator<Item = Effect> + '_ {
+        self.0.iter().map(|status| status.effect)
+    }
+
+    #[inline]
+    pub fn is_frozen(&self) -> bool {
+        self.effects().any(|effect| effect == Effect::Freeze)
+    }
+
+    #[inline]
+    pub fn can_attack(&self) -> bool {
+        !self
+            .effects()
+            .any(|effect| matches!(effect, Effect::Freeze | Effect::Stun))
+    }
+
+    /// Factor of the speed, 0 when frozen.
+    pub fn speed_factor(&self) -> f32 {
+        if self.is_frozen() {
+            return 0.;
+        }
+        self.effects()
+            .map(|effect| match effect {
+                Effect::Slow(factor) => factor,
+                _ => 1.,
+            })
+            .product()
+    }
+
+    /// Factor of the waiting between two attacks.
+    pub fn attack_factor(&self) -> f32 {
+        self.effects()
+            .map(|effect| match effect {
+                Effect::AttackSlow(factor) => factor,
+                _ => 1.,
+            })
+            .product()
+    }
+}
+
+#[test]
+fn stacking_rules() {
+    let mut statuses = Statuses::default();
+    statuses.apply(Status::new(Effect::Slow(0.5), 10));
+    statuses.apply(Status::new(Effect::Slow(0.8), 30));
+    assert_eq!(statuses.speed_factor(), 0.5);
+    for _ in 0..20 {
+        statuses.tick();
+    }
+    assert_eq!(statuses.speed_factor(), 0.5);
+
+    for life in 1..=4 {
+        statuses.apply(Status::from_secs(Effect::Burn(life), 1.));
+    }
+    let burn = (0..FPS).map(|_| statuses.tick()).sum::<u32>();
+    assert_eq!(burn, 2 + 3 + 4);
+    assert_eq!(statuses.speed_factor(), 1.);
+
+    statuses.apply(Status::new(Effect::Freeze, 5));
+    assert!(!statuses.can_attack());
+    assert_eq!(statuses.speed_factor(), 0.);
+}
@@ -0,0 +1,318 @@
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    components::{life_scale, Collide, RangeBox},
+    enemy::Enemy,
+    projectile::Projectile,
+    status::{Status, Statuses},
+    units::{units, TurretDef},
+};
+
+/// How a turret shoots beyond its own line, its projectiles are fired by `Game`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Reach {
+    /// Lob a shell at the most advanced enemy of the adjacent lines
+    Mortar,
+    /// Hit at once every enemy in the column of the turret, on every line
+    Column,
+}
+
+/// The enemy aimed by a turret among the ones in its range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Priority {
+    /// The most advanced one
+    #[default]
+    First,
+    /// The one with the most life
+    Strongest,
+    /// The one moving the fastest
+    Fastest,
+}
+
+/// Money produced by a turret while a wave is running.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Income {
+    pub money: u32,
+    /// Time between two productions, in seconds in the units and in frames on a turret
+    pub waiting: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Turret {
+    x: f32,
+    price: u32,
+    price_text: Rc<String>,
+    level: u8,
+    projectile: Option<u8>,
+    life: u32,
+    max_life: u32,
+    hitbox: RangeBox,
+    waiting: f32,
+    attack_waiting: f32,
+    statuses: Statuses,
+    sprite: u8,
+    reach: Option<Reach>,
+    income: Option<Income>,
+    /// Frames since the last production
+    producing: f32,
+    /// Distances from the turret where it can hit, up to the end of the board by default
+    range: Option<RangeBox>,
+    priority: Priority,
+}
+
+impl Turret {
+    pub fn new(
+        x: f32,
+        price: u32,
+        level: u8,
+        projectile: Option<u8>,
+        life: u32,
+        hitbox: RangeBox,
+        attack_waiting: f32,
+    ) -> Self {
+        Self {
+            x,
+            price,
+            price_text: Rc::new(format!("{}", price)),
+            level,
+            projectile,
+            life,
+            max_life: life,
+            hitbox,
+            waiting: attack_waiting / 2.,
+            attack_waiting,
+            statuses: Statuses::default(),
+            sprite: level,
+            reach: None,
+            income: None,
+            producing: 0.,
+            range: None,
+            priority: Priority::First,
+        }
+    }
+
+    #[inline]
+    pub fn prefab_turret(level: u8) -> Option<Self> {
+        units().turret(level).map(TurretDef::build)
+    }
+
+    #[inline]
+    pub fn set_x(mut self, x: f32) -> Self {
+        self.x = x;
+        self
+    }
+
+    #[inline]
+    pub fn price(&self) -> u32 {
+        self.price
+    }
+
+    #[inline]
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// Draw the turret with the sprite of the level `sprite`.
+    #[inline]
+    pub fn with_sprite(mut self, sprite: u8) -> Self {
+        self.sprite = sprite;
+        self
+    }
+
+    #[inline]
+    pub fn sprite(&self) -> u8 {
+        self.sprite
+    }
+
+    #[inline]
+    pub fn with_reach(mut self, reach: Option<Reach>) -> Self {
+        self.reach = reach;
+        self
+    }
+
+    #[inline]
+    pub fn reach(&self) -> Option<Reach> {
+        self.reach
+    }
+
+    #[inline]
+    pub fn with_range(mut self, range: Option<RangeBox>, priority: Priority) -> Self {
+        self.range = range;
+        self.priority = priority;
+        self
+    }
+
+    /// Positions where the turret can hit, on a board seen until `visible_end`.
+    pub fn range(&self, visible_end: f32) -> RangeBox {
+        let x = self.hitbox().start();
+        match self.range {
+            Some(range) => RangeBox::new(x + range.start(), (x + range.end()).min(visible_end)),
+            None => RangeBox::new(x, visible_end),
+        }
+    }
+
+    #[inline]
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    #[inline]
+    pub fn with_income(mut self, income: Option<Income>) -> Self {
+        self.income = income;
+        self
+    }
+
+    #[inline]
+    pub fn income(&self) -> Option<Income> {
+        self.income
+    }
+
+    /// Advance of a frame of wave, return the money produced if any.
+    pub fn produce(&mut self) -> Option<u32> {
+        let income = self.income?;
+        if self.statuses.can_attack() {
+            self.producing += 1.;
+        }
+        if self.producing >= income.waiting {
+            self.producing = 0.;
+            Some(income.money)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn price_text(&self) -> Rc<String> {
+        self.price_text.clone()
+    }
+
+    #[inline]
+    pub fn refund(&self) -> u32 {
+        self.price >> 1
+    }
+
+    /// The turret this one becomes once upgraded, at the same position and with the same
+    /// proportion of life.
+    pub fn upgraded(&self) -> Option<Self> {
+        let upgrade = units().turret(self.level)?.upgrade?;
+        let mut upgraded = Self::prefab_turret(upgrade)?.set_x(self.x);
+        upgraded.life = ((upgraded.max_life as f32 * self.health()) as u32).max(1);
+        Some(upgraded)
+    }
+
+    #[inline]
+    pub fn life(&self) -> u32 {
+        self.life
+    }
+
+    #[inline]
+    pub fn max_life(&self) -> u32 {
+        self.max_life
+    }
+
+    /// Proportion of life left, between 0 and 1.
+    #[inline]
+    pub fn health(&self) -> f32 {
+        self.life as f32 / self.max_life as f32
+    }
+
+    #[inline]
+    pub fn scale(&self) -> f32 {
+        life_scale(self.health())
+    }
+
+    /// Half of the price of the missing life, so a repair is never worse than deleting and
+    /// buying the turret again.
+    #[inline]
+    pub fn repair_cost(&self) -> u32 {
+        let missing = (self.max_life - self.life) as u64;
+        (self.price as u64 * missing).div_ceil(2 * self.max_life as u64) as u32
+    }
+
+    #[inline]
+    pub fn repair(&mut self) {
+        self.life = self.max_life
+    }
+
+    #[inline]
+    pub fn wait(&mut self) {
+        let burn = self.statuses.tick();
+        self.take_damage(burn);
+        if self.statuses.can_attack() && self.waiting < self.attack_waiting {
+            self.waiting += 1. / self.statuses.attack_factor();
+        }
+    }
+
+    #[inline]
+    pub fn can_attack(&self) -> bool {
+        self.waiting >= self.attack_waiting
+            && self.projectile.is_some()
+            && self.statuses.can_attack()
+    }
+
+    #[inline]
+    pub fn apply_status(&mut self, status: Status) {
+        self.statuses.apply(status)
+    }
+
+    #[inline]
+    pub fn statuses(&self) -> &Statuses {
+        &self.statuses
+    }
+
+    #[inline]
+    pub fn hitbox(&self) -> RangeBox {
+        self.hitbox + self.x
+    }
+
+    #[inline]
+    pub fn is_dead(&self) -> bool {
+        self.life == 0
+    }
+
+    /// A turret without projectile nor income only blocks the enemies.
+    #[inline]
+    pub fn is_wall(&self) -> bool {
+        self.projectile.is_none() && self.income.is_none()
+    }
+
+    /// Return the damage taken, no more than the life left.
+    #[inline]
+    pub fn take_damage(&mut self, damage: u32) -> u32 {
+        let taken = damage.min(self.life);
+        self.life -= taken;
+        taken
+    }
+
+    pub fn shoot(&mut self) -> Option<Projectile> {
+        if self.can_attack() {
+            self.waiting = 0.;
+            Projectile::new_turret_projectile(self.projectile?, self.hitbox().start())
+        } else {
+            None
+        }
+    }
+
+    /// Shoot at the enemy `target` of the line, the projectile only hits in the range of the
+    /// turret when it has one.
+    pub fn shoot_at(&mut self, target: usize, visible_end: f32) -> Option<Projectile> {
+        let range = self.range.map(|_| self.range(visible_end));
+        let target = (self.priority != Priority::First).then_some(target);
+        Some(self.shoot()?.with_target(target, range))
+    }
+}
+
+impl Collide<&Enemy> for &Turret {
+    #[inline]
+    fn collide(&self, with: &Enemy) -> bool {
+        self.hitbox().collide(&with.hitbox())
+    }
+}
+
+impl PartialEq for Turret {
+    fn eq(&self, other: &Self) -> bool {
+        self.level == other.level
+    }
+}
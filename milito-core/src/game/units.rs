@@ -0,0 +1,534 @@
+use std::{cell::RefCell, collections::HashSet, fmt, rc::Rc};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    components::RangeBox,
+    enemy::{Ability, Enemy, EnemyProceced, Phase, PhaseEffect},
+    projectile::{Behaviour, Owner, Projectile},
+    status::StatusDef,
+    turret::{Income, Priority, Reach, Turret},
+};
+use crate::FPS;
+
+const DEFAULT_UNITS: &str = include_str!("../../data/units.ron");
+
+thread_local! {
+    static UNITS: RefCell<Rc<Units>> = RefCell::new(Rc::new(
+        Units::from_ron(DEFAULT_UNITS).expect("the default units are invalid"),
+    ));
+}
+
+/// The units used to build every prefab.
+#[inline]
+pub fn units() -> Rc<Units> {
+    UNITS.with(|units| units.borrow().clone())
+}
+
+/// Replace the units used to build the prefabs, the default ones come from `data/units.ron`.
+pub fn use_units(new_units: Units) {
+    UNITS.with(|units| *units.borrow_mut() = Rc::new(new_units))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnemyDef {
+    pub level: u8,
+    pub life: u32,
+    pub damage: u32,
+    pub reward: u32,
+    pub speed: f32,
+    pub hitbox: RangeBox,
+    pub attack_waiting: f32,
+    pub weight: u32,
+    pub rank: u8,
+    #[serde(default)]
+    pub ability: Option<Ability>,
+    /// The level of the enemy whose sprite is used, its own by default
+    #[serde(default)]
+    pub sprite: Option<u8>,
+    /// The enemies with phases are bosses, left out of the random enemies
+    #[serde(default)]
+    pub phases: Vec<Phase>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurretDef {
+    pub level: u8,
+    pub price: u32,
+    pub life: u32,
+    pub hitbox: RangeBox,
+    pub attack_waiting: f32,
+    pub projectile: Option<u8>,
+    #[serde(default)]
+    pub upgrade: Option<u8>,
+    /// The level of the turret whose sprite is used, its own by default
+    #[serde(default)]
+    pub sprite: Option<u8>,
+    #[serde(default)]
+    pub reach: Option<Reach>,
+    /// The turrets with an income do not shoot
+    #[serde(default)]
+    pub income: Option<Income>,
+    /// Distances from the turret where it can hit
+    #[serde(default)]
+    pub range: Option<RangeBox>,
+    #[serde(default)]
+    pub priority: Priority,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectileDef {
+    pub level: u8,
+    pub damage: u32,
+    pub speed: f32,
+    pub hitbox: RangeBox,
+    /// Applied to the unit hit
+    #[serde(default)]
+    pub status: Option<StatusDef>,
+    #[serde(default)]
+    pub behaviour: Option<Behaviour>,
+    /// The level of the projectile whose sprite is used, its own by default
+    #[serde(default)]
+    pub sprite: Option<u8>,
+}
+
+impl EnemyDef {
+    #[inline]
+    pub fn build(&self) -> Enemy {
+        Enemy::new(
+            self.life,
+            self.damage,
+            self.level,
+            self.reward,
+            self.speed,
+            self.hitbox,
+            self.attack_waiting,
+        )
+        .with_ability(self.ability)
+        .with_sprite(self.sprite.unwrap_or(self.level))
+        .with_phases(self.phases.clone())
+    }
+}
+
+impl TurretDef {
+    #[inline]
+    pub fn build(&self) -> Turret {
+        Turret::new(
+            0.,
+            self.price,
+            self.level,
+            self.projectile,
+            self.life,
+            self.hitbox,
+            self.attack_waiting * FPS as f32,
+        )
+        .with_sprite(self.sprite.unwrap_or(self.level))
+        .with_reach(self.reach)
+        .with_range(self.range, self.priority)
+        .with_income(self.income.map(|income| Income {
+            waiting: income.waiting * FPS as f32,
+            ..income
+        }))
+    }
+}
+
+impl ProjectileDef {
+    #[inline]
+    pub fn build(&self, x: f32, owner: Owner) -> Projectile {
+        // The projectiles of the enemies fly toward the kingdom
+        let (x, speed) = match owner {
+            Owner::Enemy => (x, -self.speed),
+            _ => (x - 1., self.speed),
+        };
+        Projectile::new(
+            x,
+            self.damage,
+            self.level,
+            speed / FPS as f32,
+            self.hitbox,
+            owner,
+        )
+        .with_status(self.status.as_ref().map(StatusDef::build))
+        .with_behaviour(self.behaviour)
+        .with_sprite(self.sprite.unwrap_or(self.level))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitsError {
+    Parse(String),
+    Duplicate {
+        kind: &'static str,
+        level: u8,
+    },
+    Invalid {
+        kind: &'static str,
+        level: u8,
+        reason: &'static str,
+    },
+    UnknownProjectile {
+        kind: &'static str,
+        level: u8,
+        projectile: u8,
+    },
+    EmptyRank(u8),
+    HealersOnlyRank(u8),
+}
+
+impl fmt::Display for UnitsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "{}", err),
+            Self::Duplicate { kind, level } => write!(f, "{} {} is defined twice", kind, level),
+            Self::Invalid {
+                kind,
+                level,
+                reason,
+            } => write!(f, "{} {} {}", kind, level, reason),
+            Self::UnknownProjectile {
+                kind,
+                level,
+                projectile,
+            } => write!(
+                f,
+                "{} {} references unknown projectile {}",
+                kind, level, projectile
+            ),
+            Self::EmptyRank(rank) => write!(f, "no enemy has the rank {}", rank),
+            Self::HealersOnlyRank(rank) => write!(f, "the rank {} only has healers", rank),
+        }
+    }
+}
+
+impl std::error::Error for UnitsError {}
+
+#[derive(Deserialize)]
+struct UnitsFile {
+    enemies: Vec<EnemyDef>,
+    turrets: Vec<TurretDef>,
+    projectiles: Vec<ProjectileDef>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Units {
+    enemies: Vec<EnemyDef>,
+    turrets: Vec<TurretDef>,
+    projectiles: Vec<ProjectileDef>,
+    ranks: [Vec<EnemyProceced>; 2],
+}
+
+impl Units {
+    pub fn from_ron(text: &str) -> Result<Self, UnitsError> {
+        let file =
+            ron::from_str::<UnitsFile>(text).map_err(|err| UnitsError::Parse(err.to_string()))?;
+        Self::new(file.enemies, file.turrets, file.projectiles)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, UnitsError> {
+        let file = serde_json::from_str::<UnitsFile>(text)
+            .map_err(|err| UnitsError::Parse(err.to_string()))?;
+        Self::new(file.enemies, file.turrets, file.projectiles)
+    }
+
+    pub fn new(
+        enemies: Vec<EnemyDef>,
+        turrets: Vec<TurretDef>,
+        projectiles: Vec<ProjectileDef>,
+    ) -> Result<Self, UnitsError> {
+        let invalid = |kind, level, reason| {
+            Err(UnitsError::Invalid {
+                kind,
+                level,
+                reason,
+            })
+        };
+
+        check_duplicates("enemy", enemies.iter().map(|e| e.level))?;
+        check_duplicates("turret", turrets.iter().map(|t| t.level))?;
+        check_duplicates("projectile", projectiles.iter().map(|p| p.level))?;
+
+        for enemy in enemies.iter() {
+            if enemy.life == 0 {
+                return invalid("enemy", enemy.level, "has no life");
+            } else if enemy.speed >= 0. {
+                return invalid("enemy", enemy.level, "must have a negative speed");
+            } else if enemy.hitbox.start() >= enemy.hitbox.end() {
+                return invalid("enemy", enemy.level, "has an empty hitbox");
+            } else if enemy.weight == 0 {
+                return invalid("enemy", enemy.level, "must have a weight");
+            } else if enemy.rank != 1 && enemy.rank != 2 {
+                return invalid("enemy", enemy.level, "must have the rank 1 or 2");
+            }
+            match enemy.ability {
+                Some(Ability::Shield(0)) => {
+                    return invalid("enemy", enemy.level, "has an empty shield")
+                }
+                Some(Ability::Healer { range, waiting, .. }) if range <= 0. || waiting <= 0. => {
+                    return invalid(
+                        "enemy",
+                        enemy.level,
+                        "must heal in a range and with a waiting",
+                    )
+                }
+                Some(Ability::Switcher { waiting }) if waiting <= 0. => {
+                    return invalid("enemy", enemy.level, "must switch lanes with a waiting")
+                }
+                Some(Ability::Ranged { range, .. }) if range <= 0. => {
+                    return invalid("enemy", enemy.level, "must shoot in a range")
+                }
+                Some(Ability::Ranged { projectile, .. })
+                    if !projectiles.iter().any(|p| p.level == projectile) =>
+                {
+                    return Err(UnitsError::UnknownProjectile {
+                        kind: "enemy",
+                        level: enemy.level,
+                        projectile,
+                    });
+                }
+                Some(Ability::Splitter { into, count }) => {
+                    match enemies.iter().find(|e| e.level == into) {
+                        Some(_) if count == 0 => {
+                            return invalid("enemy", enemy.level, "splits into no enemy")
+                        }
+                        None => {
+                            return invalid("enemy", enemy.level, "splits into an unknown enemy")
+                        }
+                        Some(into) if matches!(into.ability, Some(Ability::Splitter { .. })) => {
+                            return invalid("enemy", enemy.level, "splits into a splitter")
+                        }
+                        _ => (),
+                    }
+                }
+                _ => (),
+            }
+
+            let mut last_life = 1.;
+            for phase in enemy.phases.iter() {
+                if phase.life <= 0. || phase.life >= last_life {
+                    return invalid("enemy", enemy.level, "must have phases of decreasing life");
+                }
+                last_life = phase.life;
+                match phase.effect {
+                    PhaseEffect::SpeedUp(factor) if factor <= 0. => {
+                        return invalid("enemy", enemy.level, "must speed up by a positive factor")
+                    }
+                    PhaseEffect::Stun(duration) if duration <= 0. => {
+                        return invalid("enemy", enemy.level, "must stun for a positive duration")
+                    }
+                    PhaseEffect::Minions { level, .. } => {
+                        match enemies.iter().find(|e| e.level == level) {
+                            None => {
+                                return invalid("enemy", enemy.level, "calls an unknown minion")
+                            }
+                            Some(minion) if !minion.phases.is_empty() => {
+                                return invalid("enemy", enemy.level, "calls a boss as minion")
+                            }
+                            _ => (),
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        for turret in turrets.iter() {
+            if turret.life == 0 {
+                return invalid("turret", turret.level, "has no life");
+            } else if turret.hitbox.start() >= turret.hitbox.end() {
+                return invalid("turret", turret.level, "has an empty hitbox");
+            } else if turret.attack_waiting < 0. {
+                return invalid("turret", turret.level, "has a negative attack waiting");
+            } else if turret.reach.is_some() && turret.projectile.is_none() {
+                return invalid(
+                    "turret",
+                    turret.level,
+                    "reaches other lines without projectile",
+                );
+            }
+            if turret
+                .range
+                .is_some_and(|range| range.start() < 0. || range.start() >= range.end())
+            {
+                return invalid("turret", turret.level, "has an invalid range");
+            }
+            match turret.income {
+                Some(_) if turret.projectile.is_some() => {
+                    return invalid("turret", turret.level, "produces money and shoots")
+                }
+                Some(Income { money, waiting }) if money == 0 || waiting <= 0. => {
+                    return invalid("turret", turret.level, "must produce money with a waiting")
+                }
+                _ => (),
+            }
+            if let Some(projectile) = turret.projectile {
+                if !projectiles.iter().any(|p| p.level == projectile) {
+                    return Err(UnitsError::UnknownProjectile {
+                        kind: "turret",
+                        level: turret.level,
+                        projectile,
+                    });
+                }
+            }
+            if let Some(upgrade) = turret.upgrade {
+                match turrets.iter().find(|t| t.level == upgrade) {
+                    None => {
+                        return invalid("turret", turret.level, "upgrades to an unknown turret")
+                    }
+                    Some(upgraded) if upgraded.price < turret.price => {
+                        return invalid("turret", turret.level, "upgrades to a cheaper turret")
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        for projectile in projectiles.iter() {
+            if projectile.speed <= 0. {
+                return invalid("projectile", projectile.level, "must have a positive speed");
+            } else if projectile.hitbox.start() >= projectile.hitbox.end() {
+                return invalid("projectile", projectile.level, "has an empty hitbox");
+            } else if projectile
+                .status
+                .is_some_and(|status| status.duration <= 0. || !status.effect.is_valid())
+            {
+                return invalid("projectile", projectile.level, "has an invalid status");
+            }
+            let is_factor = |factor: f32| factor > 0. && factor <= 1.;
+            match projectile.behaviour {
+                Some(Behaviour::Pierce(0)) => {
+                    return invalid("projectile", projectile.level, "pierces no enemy")
+                }
+                Some(Behaviour::Splash { radius, factor })
+                    if radius <= 0. || !is_factor(factor) =>
+                {
+                    return invalid(
+                        "projectile",
+                        projectile.level,
+                        "must splash in a radius and with a factor",
+                    )
+                }
+                Some(Behaviour::Chain {
+                    count,
+                    range,
+                    factor,
+                }) if count == 0 || range <= 0. || !is_factor(factor) => {
+                    return invalid(
+                        "projectile",
+                        projectile.level,
+                        "must chain in a range and with a factor",
+                    )
+                }
+                _ => (),
+            }
+        }
+
+        let rank = |rank| {
+            let enemies = enemies
+                .iter()
+                .filter(|e| e.rank == rank && e.phases.is_empty())
+                .map(|e| EnemyProceced {
+                    level: e.level,
+                    weight: e.weight,
+                    ability: e.ability,
+                })
+                .collect::<Vec<EnemyProceced>>();
+            if enemies.is_empty() {
+                Err(UnitsError::EmptyRank(rank))
+            } else if enemies.iter().all(EnemyProceced::is_healer) {
+                Err(UnitsError::HealersOnlyRank(rank))
+            } else {
+                Ok(enemies)
+            }
+        };
+        let ranks = [rank(1)?, rank(2)?];
+
+        Ok(Self {
+            enemies,
+            turrets,
+            projectiles,
+            ranks,
+        })
+    }
+
+    #[inline]
+    pub fn enemy(&self, level: u8) -> Option<&EnemyDef> {
+        self.enemies.iter().find(|e| e.level == level)
+    }
+
+    /// The levels of the bosses, in increasing order.
+    pub fn bosses(&self) -> Vec<u8> {
+        let mut bosses = self
+            .enemies
+            .iter()
+            .filter(|e| !e.phases.is_empty())
+            .map(|e| e.level)
+            .collect::<Vec<u8>>();
+        bosses.sort_unstable();
+        bosses
+    }
+
+    #[inline]
+    pub fn turret(&self, level: u8) -> Option<&TurretDef> {
+        self.turrets.iter().find(|t| t.level == level)
+    }
+
+    #[inline]
+    pub fn projectile(&self, level: u8) -> Option<&ProjectileDef> {
+        self.projectiles.iter().find(|p| p.level == level)
+    }
+
+    /// The enemies available to the procedural generation, `rank` is 1 or 2.
+    #[inline]
+    pub fn rank(&self, rank: u8) -> &[EnemyProceced] {
+        &self.ranks[rank as usize - 1]
+    }
+}
+
+fn check_duplicates<I: Iterator<Item = u8>>(
+    kind: &'static str,
+    levels: I,
+) -> Result<(), UnitsError> {
+    let mut seen = HashSet::new();
+    for level in levels {
+        if !seen.insert(level) {
+            return Err(UnitsError::Duplicate { kind, level });
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn default_units_are_valid() {
+    let units = Units::from_ron(DEFAULT_UNITS).unwrap();
+    assert_eq!(units.rank(1).len(), 7);
+    assert_eq!(units.turret(4).unwrap().projectile, Some(4));
+    assert_eq!(units.turret(2).unwrap().upgrade, Some(5));
+}
+
+#[test]
+fn readable_errors() {
+    let broken = DEFAULT_UNITS.replace("projectile: Some(4)", "projectile: Some(42)");
+    let err = Units::from_ron(&broken).unwrap_err();
+    assert_eq!(err.to_string(), "turret 4 references unknown projectile 42");
+
+    let broken = DEFAULT_UNITS.replace("upgrade: Some(6)", "upgrade: Some(42)");
+    let err = Units::from_ron(&broken).unwrap_err();
+    assert_eq!(err.to_string(), "turret 3 upgrades to an unknown turret");
+
+    let broken = DEFAULT_UNITS.replace("weight: 55", "weight: 0");
+    let err = Units::from_ron(&broken).unwrap_err();
+    assert_eq!(err.to_string(), "enemy 8 must have a weight");
+
+    let broken = DEFAULT_UNITS.replace("into: 3", "into: 12");
+    let err = Units::from_ron(&broken).unwrap_err();
+    assert_eq!(err.to_string(), "enemy 12 splits into a splitter");
+
+    let broken = DEFAULT_UNITS.replace("rank: 2", "rank: 1").replace(
+        "rank: 1, ability: Some(Healer",
+        "rank: 2, ability: Some(Healer",
+    );
+    let err = Units::from_ron(&broken).unwrap_err();
+    assert_eq!(err.to_string(), "the rank 2 only has healers");
+
+    assert!(matches!(Units::from_json("{}"), Err(UnitsError::Parse(_))));
+}
@@ -1,42 +1,46 @@
 mod prefabs;
 mod procedural;
+mod script;
 
 pub use prefabs::WAVES;
+pub use script::{parse_waves, write_waves, WaveScriptError};
 
 use std::collections::HashMap;
 
-use crate::{utils::rng, FPS};
+use serde::{Deserialize, Serialize};
+
+use crate::FPS;
 
 use super::enemy::Enemy;
 
-use js_sys::Math::random as js_random;
-
-// Exemple:
-// let lvl1 = wave![
-//     1 => [1, 2,],
-//     100 => [1],
-// ];
-#[macro_export]
-macro_rules! wave {
-    ($($secs:expr => [$($lvl:expr),+ $(,)?]),*  $(,)?) => {
-        {
-            let mut troops = std::collections::HashMap::new();
-            $(
-                let levels = vec![$($lvl,)*];
-                let frame = $secs * crate::FPS;
-                troops.insert(frame, levels);
-            )*
-            Wave { troops }
-        }
-    };
-}
+/// The generated waves get a boss every `BOSS_PERIOD` waves.
+pub const BOSS_PERIOD: u32 = 10;
+/// Seconds between the last enemy of a wave and its boss.
+pub const BOSS_DELAY: u64 = 5;
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Wave {
     pub troops: HashMap<u64, Vec<u8>>,
+    /// The enemies which spawn in a given lane, as `(level, lane)`
+    #[serde(default)]
+    pub fixed: HashMap<u64, Vec<(u8, usize)>>,
+    /// The boss closing the wave
+    #[serde(default)]
+    pub boss: Option<u8>,
+}
+
+impl Wave {
+    /// The frame at which the boss spawns, after every other enemy.
+    pub fn boss_frame(&self) -> u64 {
+        self.troops
+            .keys()
+            .chain(self.fixed.keys())
+            .max()
+            .map_or(0, |frame| frame + BOSS_DELAY * FPS)
+    }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WaveLine {
     pub troops: HashMap<u64, u8>,
 }
@@ -47,7 +51,7 @@ impl WaveLine {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IteratorWaveLine {
     frame: u64,
     frame_max: u64,
@@ -67,7 +71,7 @@ impl Iterator for IteratorWaveLine {
         if self.frame <= self.frame_max {
             let item = self.troops.remove(&self.frame);
             self.frame += 1;
-            item.map(Enemy::prefab).flatten()
+            item.and_then(Enemy::prefab)
         } else {
             None
         }
@@ -0,0 +1,13 @@
+use lazy_static::lazy_static;
+
+use super::{script::parse_waves, Wave};
+use crate::game::board::BoardSize;
+
+lazy_static! {
+    /// The waves of the standard game, made for the default board.
+    pub static ref WAVES: Vec<Wave> = parse_waves(
+        include_str!("../../../data/waves.txt"),
+        BoardSize::default().lines
+    )
+    .expect("the default waves are invalid");
+}
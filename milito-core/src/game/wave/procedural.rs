@@ -1,47 +1,47 @@
 use std::collections::HashMap;
 
 use crate::{
-    game::enemy::EnemyProceced,
-    log,
-    utils::{rng, GetRandom, Median},
+    game::{enemy::EnemyProceced, units::units},
+    utils::{GetRandom, Median, Rng},
     FPS,
 };
 
-use super::Wave;
+use super::{Wave, BOSS_PERIOD};
 
 #[inline]
-fn get_duration(level: u32) -> u32 {
+fn get_duration(rng: &mut Rng, level: u32) -> u32 {
     const S: f32 = 0.45;
     const U: f32 = 0.2;
 
-    let x = rng() as f32 + 0.5;
+    let x = rng.next_f64() as f32 + 0.5;
 
     let lognormal = (1. / x * S * (2. * std::f32::consts::PI).sqrt())
         * (-(x.ln() - U).powi(2) / 2. * S.powi(2)).exp();
 
     let more_time = (lognormal - 1.) * 20.;
 
-    ((rng() as f32 * 2.5 - 1.25) * more_time) as u32 + level * 12 + 10
+    ((rng.next_f64() as f32 * 2.5 - 1.25) * more_time) as u32 + level * 12 + 10
 }
 
 #[test]
 fn ter() {
+    let mut rng = Rng::default();
     for i in 1..30 {
         let mut dura = 0;
         for _ in 0..10 {
-            dura += get_duration(i);
+            dura += get_duration(&mut rng, i);
         }
         dura /= 10;
 
         let mut _periode_count = 0;
         for _ in 0..10 {
-            _periode_count += get_periode_count(dura);
+            _periode_count += get_periode_count(&mut rng, dura);
         }
         _periode_count /= 10;
 
         let mut diff = 0;
         for _ in 0..10 {
-            diff += get_difficulty(dura, i);
+            diff += get_difficulty(&mut rng, dura, i);
         }
         diff /= 10;
         println!("[{}]: {}", i, diff)
@@ -49,18 +49,18 @@ fn ter() {
 }
 
 #[inline]
-fn get_periode_count(duration: u32) -> u32 {
-    (duration / 24 + (rng() > 0.75) as u32 * 2 + (rng() > 0.75) as u32).max(1)
+fn get_periode_count(rng: &mut Rng, duration: u32) -> u32 {
+    (duration / 24 + (rng.next_f64() > 0.75) as u32 * 2 + (rng.next_f64() > 0.75) as u32).max(1)
 }
 
 #[inline]
-fn get_difficulty(duration: u32, level: u32) -> u32 {
-    (duration as f64 * (1.8 + rng() / 7.) + 1.3f64.powi(level as i32 + 10) * 3.) as u32
+fn get_difficulty(rng: &mut Rng, duration: u32, level: u32) -> u32 {
+    (duration as f64 * (1.8 + rng.next_f64() / 7.) + 1.3f64.powi(level as i32 + 10) * 3.) as u32
 }
 
 #[inline]
-fn get_shift(min: u32, max: f64, power: f64) -> u32 {
-    let x = rng() - 1.;
+fn get_shift(rng: &mut Rng, min: u32, max: f64, power: f64) -> u32 {
+    let x = rng.next_f64() - 1.;
     (max * max.powf(x * power)) as u32 + min
 }
 
@@ -89,8 +89,8 @@ struct Duration {
 
 impl Duration {
     #[inline]
-    fn get_new_position(&self) -> u64 {
-        let position_relative = (rng() * self.duration as f64 * FPS as f64).floor() as u64;
+    fn get_new_position(&self, rng: &mut Rng) -> u64 {
+        let position_relative = (rng.next_f64() * self.duration as f64 * FPS as f64).floor() as u64;
         position_relative + self.start as u64 * FPS
     }
 }
@@ -108,36 +108,45 @@ struct WavePerioded {
 }
 
 impl Wave {
-    pub fn generate(level: u32) -> Self {
-        let duration = get_duration(level);
-        let periode_count = get_periode_count(duration);
-        let difficulty = get_difficulty(duration, level);
+    /// Generate a wave of the given difficulty level, for a board of `lanes` lines.
+    pub fn generate(rng: &mut Rng, level: u32, lanes: usize) -> Self {
+        let duration = get_duration(rng, level);
+        let periode_count = get_periode_count(rng, duration);
+        let difficulty = get_difficulty(rng, duration, level);
 
         let min_space = duration / (periode_count * 3);
         let max_space = (duration * 2) / periode_count;
         let frac = duration / 10;
 
-        let mut wave = WaveMarked::random(duration, periode_count);
+        let mut wave = WaveMarked::random(rng, duration, periode_count);
 
         let mut pass = 0;
-        while !wave.spread_marker(min_space, max_space, frac, pass > 10) {
+        while !wave.spread_marker(rng, min_space, max_space, frac, pass > 10) {
             pass += 1;
-            wave = WaveMarked::random(duration, periode_count);
+            wave = WaveMarked::random(rng, duration, periode_count);
         }
 
         let mut wave = WavePerioded::from_markers(wave, difficulty, level);
 
         let brutal_wave_count = ((wave.len() - 1) / 2).saturating_sub(1);
 
-        wave.assign_periode_type(brutal_wave_count);
+        wave.assign_periode_type(rng, brutal_wave_count);
 
         wave.assign_difficulty();
 
-        wave.add_enemies();
+        wave.add_enemies(rng);
 
-        wave.pack_enemies();
+        wave.pack_enemies(rng, lanes);
 
-        Self::from_wave_perioded(wave).unwrap()
+        let mut wave = Self::from_wave_perioded(wave).unwrap();
+        if level.is_multiple_of(BOSS_PERIOD) {
+            // The bosses take turns, from the weakest one
+            let bosses = units().bosses();
+            wave.boss = bosses
+                .get((level / BOSS_PERIOD - 1) as usize % bosses.len().max(1))
+                .copied();
+        }
+        wave
     }
 
     fn from_wave_perioded(wave: WavePerioded) -> Option<Self> {
@@ -156,14 +165,17 @@ impl Wave {
                 acc
             });
 
-        troops.map(|troops| Self { troops })
+        troops.map(|troops| Self {
+            troops,
+            ..Default::default()
+        })
     }
 }
 
 impl WaveMarked {
-    fn random(duration: u32, periode_count: u32) -> Self {
+    fn random(rng: &mut Rng, duration: u32, periode_count: u32) -> Self {
         let mut markers = (0..periode_count - 1)
-            .map(|_| (rng() * duration as f64) as Marker)
+            .map(|_| (rng.next_f64() * duration as f64) as Marker)
             .collect::<Vec<Marker>>();
         markers.sort_unstable();
 
@@ -172,7 +184,14 @@ impl WaveMarked {
         Self { markers }
     }
 
-    fn spread_marker(&mut self, min_space: u32, max_space: u32, frac: u32, fuck_it: bool) -> bool {
+    fn spread_marker(
+        &mut self,
+        rng: &mut Rng,
+        min_space: u32,
+        max_space: u32,
+        frac: u32,
+        fuck_it: bool,
+    ) -> bool {
         let mut pass = 0;
 
         while self.is_not_spread(min_space, max_space) {
@@ -197,10 +216,10 @@ impl WaveMarked {
                 let marker = self.markers.get_mut(i).unwrap();
 
                 if (left_too_close || right_too_far) && (!right_too_close || !left_too_far) {
-                    let shift = get_shift(1, frac as f64, 0.8);
+                    let shift = get_shift(rng, 1, frac as f64, 0.8);
                     *marker += shift;
                 } else if (!left_too_close || !right_too_far) && (right_too_close || left_too_far) {
-                    let shift = get_shift(1, frac as f64, 0.8);
+                    let shift = get_shift(rng, 1, frac as f64, 0.8);
                     *marker -= marker.saturating_sub(shift);
                 }
 
@@ -255,9 +274,9 @@ impl WavePerioded {
         }
     }
 
-    fn assign_periode_type(&mut self, brutal_wave_count: usize) {
+    fn assign_periode_type(&mut self, rng: &mut Rng, brutal_wave_count: usize) {
         if let Some(periode) = self.periodes.last_mut() {
-            periode.type_ = PeriodeType::new_random_brutal()
+            periode.type_ = PeriodeType::new_random_brutal(rng)
         }
 
         let mut spaces = self.periodes[0..self.periodes.len().saturating_sub(1)]
@@ -274,7 +293,7 @@ impl WavePerioded {
                 .min_by(|(_, (_, a)), (_, (_, b))| a.partial_cmp(b).unwrap())
             {
                 if let Some(periode) = self.periodes.get_mut(*idx) {
-                    periode.type_ = PeriodeType::new_random_brutal()
+                    periode.type_ = PeriodeType::new_random_brutal(rng)
                 }
                 spaces.remove(space_idx);
             }
@@ -290,10 +309,10 @@ impl WavePerioded {
                 .enumerate()
                 .min_by(|(_, (_, a)), (_, (_, b))| a.partial_cmp(b).unwrap())
             {
-                self.fix_one_of_tree_aligned();
+                self.fix_one_of_tree_aligned(rng);
 
                 if let Some(new_periode) = self.periodes.get_mut(*idx) {
-                    new_periode.type_ = PeriodeType::new_random_brutal();
+                    new_periode.type_ = PeriodeType::new_random_brutal(rng);
                     spaces.remove(space_idx);
                 }
             }
@@ -347,7 +366,7 @@ impl WavePerioded {
         }
     }
 
-    fn add_enemies(&mut self) {
+    fn add_enemies(&mut self, rng: &mut Rng) {
         let proba_rank2_enemies = self.proba_to_have_rank2_enemies();
 
         for periode in self.periodes.iter_mut() {
@@ -358,7 +377,8 @@ impl WavePerioded {
                 let mut for_block = (diff_points as f32 * 0.9) as u32;
                 diff_points = (diff_points as f32 * 0.1) as u32;
 
-                let enemy_ref = EnemyProceced::new_random(proba_rank2_enemies);
+                // A block of healers could hardly be killed
+                let enemy_ref = EnemyProceced::new_random_fighter(rng, proba_rank2_enemies);
                 if let EnemyStorage::Free(ref mut storage) = periode.enemies {
                     storage.insert(enemy_ref.level, 0);
                 }
@@ -376,8 +396,8 @@ impl WavePerioded {
             }
 
             while diff_points > 0 {
-                let enemy = EnemyProceced::new_random(proba_rank2_enemies);
-                if !is_brutal_periode && enemy.level % 4 == 0 {
+                let enemy = EnemyProceced::new_random(rng, proba_rank2_enemies);
+                if !is_brutal_periode && enemy.level.is_multiple_of(4) {
                     continue;
                 }
 
@@ -395,16 +415,18 @@ impl WavePerioded {
         }
     }
 
-    fn pack_enemies(&mut self) {
+    fn pack_enemies(&mut self, rng: &mut Rng, lanes: usize) {
         for periode in self.periodes.iter_mut() {
             let mut new_storage: HashMap<u64, Vec<u8>> = HashMap::new();
             let duration = periode.duration.clone();
             if let EnemyStorage::Free(ref mut storage) = periode.enemies {
                 let mut levels = storage.keys().cloned().collect::<Vec<u8>>();
+                // The iteration order of a HashMap is not stable between two runs
+                levels.sort_unstable();
 
                 while !levels.is_empty() {
                     let (level, idx) = levels
-                        .get_random_and_index()
+                        .get_random_and_index(rng)
                         .map(|(lvl, idx)| (*lvl, idx))
                         .unwrap();
 
@@ -413,20 +435,22 @@ impl WavePerioded {
                     if *quantity == 0 {
                         levels.remove(idx);
                     }
-                    let mut position = duration.get_new_position();
+                    let mut position = duration.get_new_position(rng);
                     let mut echec = false;
                     let mut pass = 0;
-                    while new_storage.contains_key(&position) && new_storage[&position].len() >= 5 {
+                    while new_storage.contains_key(&position)
+                        && new_storage[&position].len() >= lanes
+                    {
                         pass += 1;
                         if pass > 10 {
                             echec = true;
                             break;
                         }
-                        position = duration.get_new_position();
+                        position = duration.get_new_position(rng);
                     }
 
                     if !echec {
-                        let frame = new_storage.entry(position).or_insert_with(Vec::new);
+                        let frame = new_storage.entry(position).or_default();
                         frame.push(level);
                     }
                 }
@@ -452,7 +476,7 @@ impl WavePerioded {
         }
     }
 
-    fn fix_one_of_tree_aligned(&mut self) {
+    fn fix_one_of_tree_aligned(&mut self, rng: &mut Rng) {
         if let Some((_, periodes)) = self.periodes.split_last() {
             let mut count = 0;
             if let Some((i, _)) = periodes.iter().enumerate().find(|periode| {
@@ -463,12 +487,15 @@ impl WavePerioded {
                 }
                 count == 3
             }) {
-                if let Some(periode) = self.periodes.get_mut(i - (rng() * 3.) as usize) {
+                if let Some(periode) = self.periodes.get_mut(i - (rng.next_f64() * 3.) as usize) {
                     periode.type_ = PeriodeType::Bruine
                 }
             } else if count == 2 {
                 let len = self.periodes.len();
-                if let Some(periode) = self.periodes.get_mut(len - (rng() * 2.) as usize - 2) {
+                if let Some(periode) = self
+                    .periodes
+                    .get_mut(len - (rng.next_f64() * 2.) as usize - 2)
+                {
                     periode.type_ = PeriodeType::Bruine
                 }
             }
@@ -511,8 +538,8 @@ impl PeriodeType {
     }
 
     #[inline]
-    fn new_random_brutal() -> Self {
-        if rng() > 0.55 {
+    fn new_random_brutal(rng: &mut Rng) -> Self {
+        if rng.next_f64() > 0.55 {
             PeriodeType::Block
         } else {
             PeriodeType::Group
@@ -0,0 +1,245 @@
+use std::{collections::BTreeSet, fmt, fmt::Write};
+
+use super::Wave;
+use crate::game::units::units;
+use crate::FPS;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaveScriptError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for WaveScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for WaveScriptError {}
+
+/// Parse a wave script for a board of `lanes` lines, its format is described at the top of
+/// `data/waves.txt`.
+pub fn parse_waves(script: &str, lanes: usize) -> Result<Vec<Wave>, WaveScriptError> {
+    let mut waves = Vec::new();
+
+    for (line_idx, line) in script.lines().enumerate() {
+        let error = |column: usize, message: String| WaveScriptError {
+            line: line_idx + 1,
+            column: column + 1,
+            message,
+        };
+
+        let content = line.split('#').next().unwrap_or_default();
+        let start = content.len() - content.trim_start().len();
+        let content = content.trim();
+
+        if content.is_empty() {
+            continue;
+        } else if content == "wave" {
+            waves.push(Wave::default());
+            continue;
+        } else if let Some(boss) = content.strip_prefix("boss ") {
+            let wave = waves
+                .last_mut()
+                .ok_or_else(|| error(start, "expected `wave` before the boss".to_owned()))?;
+            let column = start + content.len() - boss.trim_start().len();
+            let level = boss
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|level| units().enemy(*level).is_some_and(|e| !e.phases.is_empty()))
+                .ok_or_else(|| error(column, format!("unknown boss \"{}\"", boss.trim())))?;
+            if wave.boss.replace(level).is_some() {
+                return Err(error(start, "the wave already has a boss".to_owned()));
+            }
+            continue;
+        }
+
+        let wave = waves
+            .last_mut()
+            .ok_or_else(|| error(start, "expected `wave` before the first enemies".to_owned()))?;
+
+        let (time, enemies) = content
+            .split_once(':')
+            .ok_or_else(|| error(start, "expected `<time>: <enemies>`".to_owned()))?;
+
+        let frame = parse_time(time.trim())
+            .ok_or_else(|| error(start, format!("invalid time \"{}\"", time.trim())))?;
+        if wave.troops.contains_key(&frame) || wave.fixed.contains_key(&frame) {
+            return Err(error(
+                start,
+                format!("the time \"{}\" is already used in this wave", time.trim()),
+            ));
+        }
+
+        let mut levels = Vec::new();
+        let mut fixed = Vec::new();
+        let enemies_start = start + time.len() + 1;
+        for (column, token) in tokens(enemies) {
+            let column = enemies_start + column;
+            let (level, lane) = match token.split_once('@') {
+                Some((level, lane)) => (level, Some(lane)),
+                None => (token, None),
+            };
+
+            let level = level
+                .parse::<u8>()
+                .ok()
+                .filter(|level| units().enemy(*level).is_some())
+                .ok_or_else(|| error(column, format!("unknown enemy \"{}\"", level)))?;
+
+            if let Some(lane) = lane {
+                let lane = lane
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|lane| *lane < lanes)
+                    .ok_or_else(|| error(column, format!("invalid lane \"{}\"", lane)))?;
+                if fixed.iter().any(|(_, other)| *other == lane) {
+                    return Err(error(column, format!("the lane {} is already used", lane)));
+                }
+                fixed.push((level, lane));
+            } else {
+                levels.push(level);
+            }
+        }
+
+        if levels.is_empty() && fixed.is_empty() {
+            return Err(error(
+                enemies_start,
+                "expected at least one enemy".to_owned(),
+            ));
+        } else if levels.len() + fixed.len() > lanes {
+            return Err(error(
+                enemies_start,
+                format!("more than {} enemies at the same time", lanes),
+            ));
+        }
+
+        if !levels.is_empty() {
+            wave.troops.insert(frame, levels);
+        }
+        if !fixed.is_empty() {
+            wave.fixed.insert(frame, fixed);
+        }
+    }
+
+    Ok(waves)
+}
+
+/// Write waves in the format read by `parse_waves`.
+pub fn write_waves(waves: &[Wave]) -> String {
+    let mut script = String::new();
+
+    for wave in waves {
+        if !script.is_empty() {
+            script.push('\n');
+        }
+        script.push_str("wave\n");
+
+        let frames = wave
+            .troops
+            .keys()
+            .chain(wave.fixed.keys())
+            .collect::<BTreeSet<&u64>>();
+
+        for frame in frames {
+            if frame % FPS == 0 {
+                write!(script, "{}:", frame / FPS).unwrap();
+            } else {
+                write!(script, "{}f:", frame).unwrap();
+            }
+            for level in wave.troops.get(frame).into_iter().flatten() {
+                write!(script, " {}", level).unwrap();
+            }
+            for (level, lane) in wave.fixed.get(frame).into_iter().flatten() {
+                write!(script, " {}@{}", level, lane).unwrap();
+            }
+            script.push('\n');
+        }
+        if let Some(boss) = wave.boss {
+            writeln!(script, "boss {}", boss).unwrap();
+        }
+    }
+
+    script
+}
+
+#[inline]
+fn parse_time(time: &str) -> Option<u64> {
+    match time.strip_suffix('f') {
+        Some(frame) => frame.parse().ok(),
+        None => time.parse::<u64>().ok().map(|secs| secs * FPS),
+    }
+}
+
+/// Split on the whitespaces, and keep the position of each token.
+fn tokens(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_whitespace()
+        .map(move |token| (token.as_ptr() as usize - text.as_ptr() as usize, token))
+}
+
+#[test]
+fn round_trip() {
+    let waves = parse_waves(include_str!("../../../data/waves.txt"), 5).unwrap();
+    assert_eq!(waves.len(), 10);
+    assert_eq!(waves[2].troops[&(56 * FPS)], vec![2, 2]);
+    assert_eq!(parse_waves(&write_waves(&waves), 5).unwrap(), waves);
+
+    let mut rng = crate::utils::Rng::new(5);
+    let generated = vec![
+        Wave::generate(&mut rng, 3, 5),
+        Wave::generate(&mut rng, 12, 5),
+        Wave::generate(&mut rng, 10, 5),
+    ];
+    assert_eq!((generated[1].boss, generated[2].boss), (None, Some(15)));
+    assert_eq!(parse_waves(&write_waves(&generated), 5).unwrap(), generated);
+
+    let boss = parse_waves("wave\n0: 1\nboss 15", 5).unwrap();
+    assert_eq!(boss[0].boss, Some(15));
+    assert_eq!(boss[0].boss_frame(), super::BOSS_DELAY * FPS);
+    assert_eq!(parse_waves(&write_waves(&boss), 5).unwrap(), boss);
+
+    let fixed = parse_waves("wave\n0: 1 2@4\n3f: 3@0", 5).unwrap();
+    assert_eq!(fixed[0].fixed[&0], vec![(2, 4)]);
+    assert_eq!(parse_waves(&write_waves(&fixed), 5).unwrap(), fixed);
+}
+
+#[test]
+fn errors_are_located() {
+    let error = |script| parse_waves(script, 5).unwrap_err().to_string();
+
+    assert_eq!(
+        error("0: 1"),
+        "line 1, column 1: expected `wave` before the first enemies"
+    );
+    assert_eq!(
+        error("wave\n  12: 1  42"),
+        "line 2, column 10: unknown enemy \"42\""
+    );
+    assert_eq!(
+        error("wave # first\n0: 1@2 3@2"),
+        "line 2, column 8: the lane 2 is already used"
+    );
+    assert_eq!(
+        error("wave\n0: 1\n0: 2"),
+        "line 3, column 1: the time \"0\" is already used in this wave"
+    );
+    assert_eq!(
+        error("wave\nsoon: 1"),
+        "line 2, column 1: invalid time \"soon\""
+    );
+    assert_eq!(
+        error("wave\n0: 1\nboss  2"),
+        "line 3, column 7: unknown boss \"2\""
+    );
+    assert_eq!(
+        parse_waves("wave\n0: 1@3", 3).unwrap_err().to_string(),
+        "line 2, column 4: invalid lane \"3\""
+    );
+}
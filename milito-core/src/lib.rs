@@ -0,0 +1,6 @@
+// The simulation of Milito, without any web dependency.
+// The Yew front-end and the native tools are both built on top of it.
+pub mod game;
+pub mod utils;
+
+pub const FPS: u64 = 30;
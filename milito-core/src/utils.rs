@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_SEED: u64 = 0x4d69_6c69_746f;
+
+/// Seedable random number generator of the simulation (SplitMix64).
+/// See: https://prng.di.unimi.it/splitmix64.c
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rng {
+    seed: u64,
+    state: u64,
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
+}
+
+impl Rng {
+    #[inline]
+    pub fn new(seed: u64) -> Self {
+        Self { seed, state: seed }
+    }
+
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Return a number in [0, 1)
+    #[inline]
+    pub fn next_f64(&mut self) -> f64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let z = self.state;
+        let z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        let z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        let z = z ^ (z >> 31);
+
+        // Keep the 53 upper bits, which is the precision of a f64
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// The seed of the daily challenge of a date (FNV-1a), the same for every player.
+pub fn seed_from_date(date: &str) -> u64 {
+    date.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+pub trait Median<T> {
+    fn median(&mut self) -> Option<T>;
+}
+
+impl Median<f32> for Vec<f32> {
+    fn median(&mut self) -> Option<f32> {
+        self.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        if self.len().is_multiple_of(2) {
+            if self.is_empty() {
+                None
+            } else {
+                Some(
+                    (self.get((self.len() / 2) - 1).unwrap() + self.get(self.len() / 2).unwrap())
+                        / 2.,
+                )
+            }
+        } else {
+            self.get(self.len() / 2).cloned()
+        }
+    }
+}
+
+pub trait GetRandom<T> {
+    fn get_random(&self, rng: &mut Rng) -> Option<&T>;
+    fn get_random_and_index(&self, rng: &mut Rng) -> Option<(&T, usize)>;
+}
+
+impl<T> GetRandom<T> for Vec<T> {
+    fn get_random(&self, rng: &mut Rng) -> Option<&T> {
+        self.get((rng.next_f64() * self.len() as f64).floor() as usize)
+    }
+
+    fn get_random_and_index(&self, rng: &mut Rng) -> Option<(&T, usize)> {
+        let index = (rng.next_f64() * self.len() as f64).floor() as usize;
+        self.get(index).map(|x| (x, index))
+    }
+}
+
+impl<T> GetRandom<T> for [T] {
+    fn get_random(&self, rng: &mut Rng) -> Option<&T> {
+        self.get((rng.next_f64() * self.len() as f64).floor() as usize)
+    }
+
+    fn get_random_and_index(&self, rng: &mut Rng) -> Option<(&T, usize)> {
+        let index = (rng.next_f64() * self.len() as f64).floor() as usize;
+        self.get(index).map(|x| (x, index))
+    }
+}
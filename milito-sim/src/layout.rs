@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+
+/// A turret placed on the board: `(level, x, y)`
+pub type Placement = (u8, usize, usize);
+
+/// The turrets to place before each wave.
+//
+// Format, one line per wave:
+//     <wave>: <level>@<x>,<y> <level>@<x>,<y> ...
+// Lines starting with `#` are comments.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Layout {
+    placements: BTreeMap<usize, Vec<Placement>>,
+}
+
+impl Layout {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut layout = Self::default();
+
+        for (idx, line) in text.lines().enumerate() {
+            let error = |message: &str| format!("layout line {}: {}", idx + 1, message);
+
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (wave, turrets) = line
+                .split_once(':')
+                .ok_or_else(|| error("expected `<wave>: <turrets>`"))?;
+            let wave = wave
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| error("invalid wave"))?;
+
+            for turret in turrets.split_whitespace() {
+                let placement = turret
+                    .split_once('@')
+                    .and_then(|(level, coord)| {
+                        let (x, y) = coord.split_once(',')?;
+                        Some((level.parse().ok()?, x.parse().ok()?, y.parse().ok()?))
+                    })
+                    .ok_or_else(|| error(&format!("invalid turret \"{}\"", turret)))?;
+                layout.placements.entry(wave).or_default().push(placement);
+            }
+        }
+
+        Ok(layout)
+    }
+
+    #[inline]
+    pub fn before_wave(&self, wave: usize) -> &[Placement] {
+        self.placements
+            .get(&wave)
+            .map(|placements| placements.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+#[test]
+fn parse_layout() {
+    let layout = Layout::parse("# opening\n1: 1@0,0 1@0,4\n\n3: 2@1,2 # more damage").unwrap();
+    assert_eq!(layout.before_wave(1), &[(1, 0, 0), (1, 0, 4)]);
+    assert_eq!(layout.before_wave(2), &[]);
+    assert_eq!(layout.before_wave(3), &[(2, 1, 2)]);
+
+    assert_eq!(
+        Layout::parse("1: 1@0,0\n2: 3@a,1").unwrap_err(),
+        "layout line 2: invalid turret \"3@a,1\""
+    );
+}
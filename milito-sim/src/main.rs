@@ -0,0 +1,216 @@
+mod layout;
+mod sim;
+
+use std::{collections::BTreeMap, fs, process, str::FromStr};
+
+use milito_core::{
+    game::{
+        board::BoardSize,
+        units::{use_units, Units},
+        wave::parse_waves,
+    },
+    FPS,
+};
+
+use layout::Layout;
+use sim::{Outcome, RunReport, Simulation};
+
+const USAGE: &str = "\
+Play a game of milito without player, to balance the units and the waves.
+
+USAGE:
+    milito-sim [OPTIONS]
+
+OPTIONS:
+    --layout <FILE>     Turrets to place before each wave, one line per wave:
+                        `<wave>: <level>@<x>,<y> ...`
+    --seed <SEED>       Seed of the first run [default: 0]
+    --runs <N>          Play the seeds SEED..SEED+N and aggregate the results [default: 1]
+    --waves <FILE>      Wave script to play instead of the standard game
+    --generated <N>     Generated waves played after the ones of --waves [default: 0]
+    --units <FILE>      Units file (.ron or .json) replacing the default units
+    --money <AMOUNT>    Money at the beginning of the game [default: 0]
+    --quiet             Only print the aggregated results
+    --help              Print this message";
+
+struct Args {
+    layout: Option<String>,
+    seed: u64,
+    runs: u64,
+    waves: Option<String>,
+    generated: u32,
+    units: Option<String>,
+    money: u32,
+    quiet: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        layout: None,
+        seed: 0,
+        runs: 1,
+        waves: None,
+        generated: 0,
+        units: None,
+        money: 0,
+        quiet: false,
+    };
+
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .ok_or_else(|| format!("missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "--layout" => args.layout = Some(value()?),
+            "--seed" => args.seed = number(value()?)?,
+            "--runs" => args.runs = number(value()?)?,
+            "--waves" => args.waves = Some(value()?),
+            "--generated" => args.generated = number(value()?)?,
+            "--units" => args.units = Some(value()?),
+            "--money" => args.money = number(value()?)?,
+            "--quiet" => args.quiet = true,
+            "--help" => {
+                println!("{}", USAGE);
+                process::exit(0)
+            }
+            _ => return Err(format!("unknown argument \"{}\"", arg)),
+        }
+    }
+
+    Ok(args)
+}
+
+fn number<T: FromStr>(value: String) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid number \"{}\"", value))
+}
+
+fn read(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))
+}
+
+fn run() -> Result<(), String> {
+    let args = parse_args()?;
+
+    if let Some(path) = args.units.as_deref() {
+        let text = read(path)?;
+        let units = if path.ends_with(".json") {
+            Units::from_json(&text)
+        } else {
+            Units::from_ron(&text)
+        }
+        .map_err(|err| format!("{}: {}", path, err))?;
+        use_units(units);
+    }
+
+    let layout = match args.layout.as_deref() {
+        Some(path) => Layout::parse(&read(path)?).map_err(|err| format!("{}: {}", path, err))?,
+        None => Layout::default(),
+    };
+
+    let waves = match args.waves.as_deref() {
+        Some(path) => Some(
+            parse_waves(&read(path)?, BoardSize::default().lines)
+                .map_err(|err| format!("{}: {}", path, err))?,
+        ),
+        None => None,
+    };
+
+    let simulation = Simulation {
+        layout: &layout,
+        waves: waves.as_deref(),
+        generated: args.generated,
+        money: args.money,
+    };
+
+    let reports = (args.seed..args.seed.saturating_add(args.runs))
+        .map(|seed| simulation.run(seed))
+        .collect::<Vec<RunReport>>();
+
+    if !args.quiet {
+        reports.iter().for_each(print_run);
+    }
+    if reports.len() > 1 {
+        print_summary(&reports);
+    }
+
+    Ok(())
+}
+
+fn print_run(report: &RunReport) {
+    println!("seed {}", report.seed);
+    println!("  wave  earned  lost  leaked  refused  seconds");
+    for wave in report.waves.iter() {
+        println!(
+            "  {:>4}  {:>6}  {:>4}  {:>6}  {:>7}  {:>7.1}",
+            wave.wave,
+            wave.money_earned,
+            wave.turrets_lost,
+            wave.enemies_leaked,
+            wave.turrets_refused,
+            wave.frames as f32 / FPS as f32
+        );
+    }
+    println!(
+        "  {:?} at the wave {}, frame {}\n",
+        report.outcome,
+        report.last_wave(),
+        report.frame
+    );
+}
+
+fn print_summary(reports: &[RunReport]) {
+    let runs = reports.len() as f32;
+    let count = |outcome| reports.iter().filter(|r| r.outcome == outcome).count();
+
+    println!("{} runs", reports.len());
+    println!(
+        "  victories {} ({:.1}%), defeats {}, stuck {}",
+        count(Outcome::Victory),
+        count(Outcome::Victory) as f32 / runs * 100.,
+        count(Outcome::Defeat),
+        count(Outcome::Stuck)
+    );
+
+    let mut defeats = BTreeMap::<usize, usize>::new();
+    for report in reports.iter().filter(|r| r.outcome == Outcome::Defeat) {
+        *defeats.entry(report.last_wave()).or_default() += 1;
+    }
+    for (wave, amount) in defeats {
+        println!("  lost at the wave {}: {}", wave, amount);
+    }
+
+    // Average of each wave over the runs which played it
+    let mut waves = BTreeMap::<usize, (u32, f32, f32, f32, f32)>::new();
+    for wave in reports.iter().flat_map(|r| r.waves.iter()) {
+        let entry = waves.entry(wave.wave).or_default();
+        entry.0 += 1;
+        entry.1 += wave.money_earned as f32;
+        entry.2 += wave.turrets_lost as f32;
+        entry.3 += wave.enemies_leaked as f32;
+        entry.4 += wave.frames as f32 / FPS as f32;
+    }
+    println!("  wave  played  earned  lost  leaked  seconds");
+    for (wave, (played, earned, lost, leaked, seconds)) in waves {
+        let played_f = played as f32;
+        println!(
+            "  {:>4}  {:>6}  {:>6.0}  {:>4.1}  {:>6.2}  {:>7.1}",
+            wave,
+            played,
+            earned / played_f,
+            lost / played_f,
+            leaked / played_f,
+            seconds / played_f
+        );
+    }
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {}\n\n{}", err, USAGE);
+        process::exit(1)
+    }
+}
@@ -0,0 +1,178 @@
+use milito_core::{
+    game::{event::Event, wave::Wave, Game, GameStats},
+    FPS,
+};
+
+use crate::layout::Layout;
+
+/// A wave lasting longer than this is considered stuck.
+const MAX_WAVE_FRAMES: u64 = 30 * 60 * FPS;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Victory,
+    Defeat,
+    /// A wave did not end before `MAX_WAVE_FRAMES`
+    Stuck,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct WaveReport {
+    pub wave: usize,
+    pub money_earned: u32,
+    pub turrets_lost: usize,
+    pub enemies_leaked: usize,
+    /// Placements refused because of the money or an occupied cell
+    pub turrets_refused: usize,
+    pub frames: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunReport {
+    pub seed: u64,
+    pub outcome: Outcome,
+    /// Frame of the victory or the defeat
+    pub frame: u64,
+    pub waves: Vec<WaveReport>,
+}
+
+impl RunReport {
+    /// The last wave played, the one lost in case of defeat.
+    #[inline]
+    pub fn last_wave(&self) -> usize {
+        self.waves.last().map(|wave| wave.wave).unwrap_or_default()
+    }
+}
+
+pub struct Simulation<'a> {
+    pub layout: &'a Layout,
+    /// The waves to play, the standard game is used when there is none
+    pub waves: Option<&'a [Wave]>,
+    pub generated: u32,
+    pub money: u32,
+}
+
+impl<'a> Simulation<'a> {
+    pub fn run(&self, seed: u64) -> RunReport {
+        let mut game = match self.waves {
+            Some(waves) => {
+                let mut game = Game::with_seed(seed);
+                game.add_waves(waves.to_vec());
+                game.generate_waves(self.generated);
+                game.assign_line_for_enemies();
+                game
+            }
+            None => Game::standard(seed),
+        };
+        game.money = self.money;
+
+        let mut waves = Vec::new();
+        while game.stats == GameStats::Playing {
+            let wave = game.wave() + 1;
+            let mut report = WaveReport {
+                wave,
+                ..Default::default()
+            };
+
+            for &(level, x, y) in self.layout.before_wave(wave) {
+                if !game.place_turret(level, x, y) {
+                    report.turrets_refused += 1;
+                }
+            }
+
+            game.start_next_wave();
+            let start = game.frame();
+            loop {
+                game.process();
+
+                let mut cleared = false;
+                for event in game.events() {
+                    match event {
+                        Event::EnemyKilled { reward, .. } => report.money_earned += reward,
+                        Event::MoneyProduced { money, .. } => report.money_earned += money,
+                        Event::TurretDestroyed { .. } => report.turrets_lost += 1,
+                        Event::LaneBreached { .. } => report.enemies_leaked += 1,
+                        Event::WaveCleared { .. } => cleared = true,
+                        _ => (),
+                    }
+                }
+
+                if game.stats != GameStats::Playing
+                    || cleared
+                    || game.frame() - start >= MAX_WAVE_FRAMES
+                {
+                    break;
+                }
+            }
+            report.frames = game.frame() - start;
+
+            let stuck = report.frames >= MAX_WAVE_FRAMES;
+            waves.push(report);
+            if stuck {
+                return RunReport {
+                    seed,
+                    outcome: Outcome::Stuck,
+                    frame: game.frame(),
+                    waves,
+                };
+            }
+
+            // The victory is only declared once there is no more wave to start
+            if game.stats == GameStats::Playing && game.wave() == game.wave_count() {
+                game.start_next_wave();
+                game.process();
+            }
+        }
+
+        RunReport {
+            seed,
+            outcome: if game.stats == GameStats::Victory {
+                Outcome::Victory
+            } else {
+                Outcome::Defeat
+            },
+            frame: game.frame(),
+            waves,
+        }
+    }
+}
+
+#[test]
+fn simulate_a_run() {
+    let layout = Layout::parse("1: 1@0,0 1@0,1 1@0,2 1@0,3 1@0,4").unwrap();
+    let simulation = Simulation {
+        layout: &layout,
+        waves: None,
+        generated: 0,
+        money: 500,
+    };
+
+    let report = simulation.run(7);
+    assert_eq!(report, simulation.run(7));
+    assert_eq!(report.waves[0].turrets_refused, 0);
+    assert_ne!(report.outcome, Outcome::Stuck);
+    if report.outcome == Outcome::Defeat {
+        assert!(report.waves.last().unwrap().enemies_leaked > 0);
+    }
+}
+
+#[test]
+fn count_the_bank_income() {
+    // The bank is unlocked at the second wave
+    let layout = Layout::parse("1: 1@0,0\n3: 13@1,1").unwrap();
+    let waves = milito_core::game::wave::parse_waves(
+        "wave\n0: 1@0\n\nwave\n0: 1@0\n\nwave\n0: 1@0\n60: 1@0",
+        5,
+    )
+    .unwrap();
+    let simulation = Simulation {
+        layout: &layout,
+        waves: Some(&waves),
+        generated: 0,
+        money: 500,
+    };
+
+    let report = simulation.run(3);
+    assert_eq!(report.waves[1].money_earned, 10);
+    assert!(report.waves[2].money_earned > 2 * 10);
+}
@@ -0,0 +1,22 @@
+use yew::prelude::*;
+
+use milito_core::game::enemy::Enemy;
+
+/// The large health bar of a boss, with a mark at the life of each of its phases.
+pub fn boss_bar(boss: &Enemy) -> Html {
+    let width = format!("width: {}%", boss.health() * 100.);
+
+    html! {
+        <div class="boss-bar">
+            <span>{ format!("Boss, phase {}/{}", boss.phase() + 1, boss.phases().len() + 1) }</span>
+            <div class="boss-health">
+                <div class="boss-life" style=width/>
+                { for boss.phases().iter().enumerate().map(|(idx, phase)| {
+                    let classes = if idx < boss.phase() { "phase-mark passed" } else { "phase-mark" };
+                    let left = format!("left: {}%", phase.life * 100.);
+                    html_nested! { <div class=classes style=left/> }
+                }) }
+            </div>
+        </div>
+    }
+}
@@ -17,6 +17,9 @@ pub struct CardProps<T: Clone + PartialEq> {
     pub onclick_value: Rc<T>,
     #[prop_or(false)]
     pub selected: bool,
+    /// Tell apart the cards sharing a sprite
+    #[prop_or_default]
+    pub badge: Option<String>,
 }
 
 pub enum Msg {
@@ -58,6 +61,7 @@ impl<T: 'static + Clone + PartialEq> Component for Card<T> {
         html! {
             <div class=classes!(card_classes) onclick=self.link.callback(|_| Msg::Onclick)>
                 <div class=classes!(card_div_classes)></div>
+                { for self.props.badge.iter().map(|badge| html! { <span class="badge">{ badge }</span> }) }
                 <div>
                     { self.props.price_text.clone() }
                 </div>
@@ -1,6 +1,6 @@
 use yew::prelude::*;
 
-use crate::game::GOD_LEVEL_MAX;
+use milito_core::game::GOD_LEVEL_MAX;
 
 pub struct Footer {
     link: ComponentLink<Self>,
@@ -11,9 +11,13 @@ pub struct Footer {
 pub struct FooterProps {
     pub god_level: u32,
     pub wave: usize,
+    /// Only shown in endless mode
+    pub score: Option<u32>,
     pub delete_mode: bool,
+    pub repair_mode: bool,
     pub active_god: Callback<()>,
     pub toggle_delete_mode: Callback<()>,
+    pub toggle_repair_mode: Callback<()>,
     pub start_next_wave: Callback<()>,
     pub wave_ended: bool,
 }
@@ -21,6 +25,7 @@ pub struct FooterProps {
 pub enum Msg {
     God,
     DeleteMode,
+    RepairMode,
     NextWave,
 }
 
@@ -45,6 +50,7 @@ impl Component for Footer {
         match msg {
             Msg::God => self.props.active_god.emit(()),
             Msg::DeleteMode => self.props.toggle_delete_mode.emit(()),
+            Msg::RepairMode => self.props.toggle_repair_mode.emit(()),
             Msg::NextWave => self.props.start_next_wave.emit(()),
         }
         false
@@ -62,6 +68,15 @@ impl Component for Footer {
             }
         );
 
+        let repair_classes = format!(
+            "info-button {}",
+            if self.props.repair_mode {
+                "selected"
+            } else {
+                ""
+            }
+        );
+
         html! {
             <footer>
                 <button class="god" onclick=self.link.callback(|_| Msg::God) disabled=self.props.god_level != GOD_LEVEL_MAX>
@@ -71,9 +86,22 @@ impl Component for Footer {
                     <button class=classes!(trashcan_classes) onclick=self.link.callback(|_| Msg::DeleteMode)>
                         { "🗑️" }
                     </button>
+                    <button class=classes!(repair_classes) title="Repair a turret" onclick=self.link.callback(|_| Msg::RepairMode)>
+                        { "🔧" }
+                    </button>
                     <button class="info-button" onclick=self.link.callback(|_| Msg::NextWave) disabled=!self.props.wave_ended>
                         { "➡" }
                     </button>
+                    { if let Some(score) = self.props.score {
+                        html_nested! {
+                            <div class="wave">
+                                <span>{ "Score " }</span>
+                                <span>{ score }</span>
+                            </div>
+                        }
+                    } else {
+                        html_nested! {}
+                    }}
                     <div class="wave">
                         <span>{ "Wave " }</span>
                         <span>{ self.props.wave }</span>
@@ -2,7 +2,32 @@ use std::cell::RefCell;
 
 use yew::prelude::*;
 
-use crate::game::{enemy::Enemy, projectile::Projectile};
+use milito_core::{
+    game::{
+        board::Terrain,
+        components::life_scale,
+        enemy::{Ability, Enemy},
+        projectile::{Owner, Projectile},
+        status::{Effect, Statuses},
+    },
+    FPS,
+};
+
+/// Number of frames a damage number stays on the board.
+pub const DAMAGE_LIFETIME: u32 = FPS as u32;
+
+/// Number of frames the beam of a column laser stays on the board.
+pub const BEAM_LIFETIME: u32 = FPS as u32 / 4;
+
+/// A damage number floating above an enemy hit, or the money produced by a turret.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloatingDamage {
+    pub x: f32,
+    pub amount: u32,
+    pub money: bool,
+    /// Number of frames since the hit
+    pub age: u32,
+}
 
 pub struct GameRow {
     link: ComponentLink<Self>,
@@ -12,13 +37,25 @@ pub struct GameRow {
 #[derive(Debug, Properties, PartialEq, Clone)]
 pub struct GameRowProps {
     pub player_level: Option<u8>,
-    pub cells: Vec<Option<u8>>,
+    /// The sprite, the health and the class of the statuses of the turrets
+    pub cells: Vec<Option<(u8, f32, &'static str)>>,
+    pub terrain: Option<Terrain>,
+    pub cells_terrain: Vec<Option<Terrain>>,
+    /// The turrets which can be upgraded or repaired right now, depending on the mode
+    pub selectable: Vec<bool>,
     pub execute_action: Callback<(usize, usize)>,
     pub y: usize,
+    /// Width of a cell, in percent of the row
+    pub cell_size: f32,
     pub show_grid: bool,
     pub delete_mode: bool,
+    pub upgrade_mode: bool,
+    pub repair_mode: bool,
     pub projectiles: RefCell<Vec<Projectile>>,
     pub enemies: RefCell<Vec<Enemy>>,
+    pub damages: Vec<FloatingDamage>,
+    /// The columns struck by a beam, with the number of frames since the strike
+    pub beams: Vec<(usize, u32)>,
 }
 
 pub enum Msg {
@@ -59,9 +96,13 @@ impl Component for GameRow {
             html_nested!()
         };
 
+        let player_slot = format!("width: {}%", self.props.cell_size);
+
+        let row_classes = format!("game-row {}", terrain_class(self.props.terrain));
+
         html! {
-            <div class="game-row">
-                <div>
+            <div class=classes!(row_classes)>
+                <div class="player-slot" style=player_slot>
                     { player }
                 </div>
                 <div class="path">
@@ -70,31 +111,88 @@ impl Component for GameRow {
                     <img src="assets/images/laser_balise.png" alt="balise" />
                 </div>
                 { for self.props.projectiles.borrow().iter().map(|proj| {
-                    let player_proj = if proj.from_player() { "player-" } else { "" };
-                    let projectile_classes = format!("{}projectile-img level{}-32 free projectile", player_proj, proj.level());
+                    let sprite = match proj.owner() {
+                        Owner::Player => "player-projectile-img",
+                        Owner::Turret => "projectile-img",
+                        Owner::Enemy => "projectile-img enemy-projectile",
+                    };
+                    let projectile_classes = format!("{} level{}-32 free projectile", sprite, proj.sprite());
                     let projectile_pos = format!("left: {}%", proj.x());
                     html_nested! {
                         <div class=classes!(projectile_classes) style=projectile_pos/>
                     }
                 }) }
                 { for self.props.enemies.borrow().iter().map(|enemy| {
-                    let enemy_classes = format!("enemy-img level{}-128 free", enemy.level());
-                    let enemy_datas = format!("left: {}%; {}", enemy.x(), enemy.css_transform());
-                    html_nested! {
-                        <div class=classes!(enemy_classes) style=enemy_datas/>
+                    let enemy_classes = format!(
+                        "enemy-img level{}-128 free {} {} {}",
+                        enemy.sprite(),
+                        ability_class(enemy),
+                        status_class(enemy.statuses()),
+                        if enemy.is_boss() { "boss" } else { "" }
+                    );
+                    // An enemy changing of lane slides from its previous row
+                    let shift = enemy.lane_shift() * 100.;
+                    let enemy_datas = format!("left: {}%; top: {}%; {}", enemy.x(), shift, enemy.css_transform());
+                    let bar_pos = format!("left: {}%; top: {}%", enemy.x(), 10. + shift);
+                    html! {
+                        <>
+                            <div class=classes!(enemy_classes) style=enemy_datas/>
+                            <div class="enemy-health free" style=bar_pos>
+                                { health_bar(enemy.health()) }
+                            </div>
+                        </>
+                    }
+                }) }
+                { for self.props.beams.iter().map(|&(x, age)| {
+                    let beam_pos = format!(
+                        "left: {}%; width: {}%; opacity: {}",
+                        (x + 1) as f32 * self.props.cell_size,
+                        self.props.cell_size,
+                        1. - age as f32 / BEAM_LIFETIME as f32
+                    );
+                    html_nested! { <div class="beam free" style=beam_pos/> }
+                }) }
+                { for self.props.damages.iter().map(|damage| {
+                    let progress = damage.age as f32 / DAMAGE_LIFETIME as f32;
+                    let damage_pos = format!("left: {}%; top: {}%; opacity: {}", damage.x, 10. - 20. * progress, 1. - progress);
+                    if damage.money {
+                        html_nested! {
+                            <div class="damage-number money free" style=damage_pos>{ format!("+{}", damage.amount) }</div>
+                        }
+                    } else {
+                        html_nested! {
+                            <div class="damage-number free" style=damage_pos>{ damage.amount }</div>
+                        }
                     }
                 }) }
                 <div class="board-row">
                     { for self.props.cells.iter().enumerate().map(|(x, turret)| {
-                        let turret = turret.map(|level| {
-                            let turret_classes = format!("turret-img level{}-128 free", level);
-                            html_nested!(
-                                <div class=classes!(turret_classes) />
-                            )
+                        let turret = turret.map(|(sprite, health, status)| {
+                            let turret_classes = format!("turret-img level{}-128 free {}", sprite, status);
+                            let turret_transform = format!("transform:scale({});", life_scale(health));
+                            html! {
+                                <>
+                                    <div class=classes!(turret_classes) style=turret_transform />
+                                    { health_bar(health) }
+                                </>
+                            }
                         });
+                        let cell_terrain = self.props.cells_terrain[x];
+                        let blocked = [cell_terrain, self.props.terrain].contains(&Some(Terrain::Blocked));
+                        let target_mode = self.props.upgrade_mode || self.props.repair_mode;
+                        let disabled = if target_mode {
+                            !self.props.selectable[x]
+                        } else {
+                            (turret.is_some() || blocked) && !self.props.delete_mode
+                        };
+                        let cell_classes = format!(
+                            "cell {} {}",
+                            terrain_class(cell_terrain),
+                            if target_mode && self.props.selectable[x] { "selectable" } else { "" }
+                        );
                         html_nested!(
-                            <button class="cell" onclick=self.link.callback(move |_| Msg::ExectuteAction(x)) disabled=!self.props.show_grid || (turret.is_some() && !self.props.delete_mode)>
-                                { turret.unwrap_or_else(|| html_nested!()) }
+                            <button class=classes!(cell_classes) onclick=self.link.callback(move |_| Msg::ExectuteAction(x)) disabled=!self.props.show_grid || disabled>
+                                { turret.unwrap_or_default() }
                             </button>
                         )
                     }) }
@@ -103,3 +201,60 @@ impl Component for GameRow {
         }
     }
 }
+
+fn terrain_class(terrain: Option<Terrain>) -> &'static str {
+    match terrain {
+        Some(Terrain::Slow) => "terrain-slow",
+        Some(Terrain::Fast) => "terrain-fast",
+        Some(Terrain::Blocked) => "terrain-blocked",
+        Some(Terrain::Bonus) => "terrain-bonus",
+        None => "",
+    }
+}
+
+fn ability_class(enemy: &Enemy) -> &'static str {
+    match enemy.ability() {
+        Some(Ability::Shield(_)) if enemy.shield() > 0 => "ability-shield",
+        Some(Ability::Healer { .. }) => "ability-healer",
+        Some(Ability::Splitter { .. }) => "ability-splitter",
+        Some(Ability::Jumper) => "ability-jumper",
+        Some(Ability::Switcher { .. }) => "ability-switcher",
+        _ => "",
+    }
+}
+
+/// The class showing the most important status of a unit.
+pub fn status_class(statuses: &Statuses) -> &'static str {
+    let effects = statuses.effects().collect::<Vec<Effect>>();
+    if effects.contains(&Effect::Freeze) {
+        "status-frozen"
+    } else if effects.contains(&Effect::Stun) {
+        "status-stunned"
+    } else if effects
+        .iter()
+        .any(|effect| matches!(effect, Effect::Burn(_)))
+    {
+        "status-burning"
+    } else if effects
+        .iter()
+        .any(|effect| matches!(effect, Effect::Slow(_)))
+    {
+        "status-slowed"
+    } else if effects
+        .iter()
+        .any(|effect| matches!(effect, Effect::AttackSlow(_)))
+    {
+        "status-weakened"
+    } else {
+        ""
+    }
+}
+
+fn health_bar(health: f32) -> Html {
+    let width = format!("width: {}%", health * 100.);
+    html! {
+        <div class="health-bar">
+            <div style=width/>
+        </div>
+    }
+}
@@ -3,7 +3,7 @@ use std::rc::Rc;
 use yew::prelude::*;
 
 use super::card::Card;
-use crate::game::turret::Turret;
+use milito_core::game::turret::Turret;
 
 #[derive(Debug, Properties, Clone, PartialEq)]
 pub struct HeaderProps {
@@ -12,7 +12,9 @@ pub struct HeaderProps {
     pub turret_selected: Option<u8>,
     pub player_level: u8,
     pub upgrade_cost_text: Rc<String>,
+    pub upgrade_mode: bool,
     pub on_turret_selected: Callback<Turret>,
+    pub toggle_upgrade_mode: Callback<()>,
     pub upgrade_player: Callback<()>,
     pub make_pause: Callback<()>,
 }
@@ -24,6 +26,7 @@ pub struct Header {
 
 pub enum Msg {
     TurretSelected(Turret),
+    UpgradeMode,
     UpgradePlayer,
     Pause,
 }
@@ -48,6 +51,7 @@ impl Component for Header {
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Msg::TurretSelected(t) => self.props.on_turret_selected.emit(t),
+            Msg::UpgradeMode => self.props.toggle_upgrade_mode.emit(()),
             Msg::UpgradePlayer => self.props.upgrade_player.emit(()),
             Msg::Pause => self.props.make_pause.emit(()),
         }
@@ -55,6 +59,15 @@ impl Component for Header {
     }
 
     fn view(&self) -> Html {
+        let upgrade_classes = format!(
+            "info-button upgrade-turret {}",
+            if self.props.upgrade_mode {
+                "selected"
+            } else {
+                ""
+            }
+        );
+
         html! {
             <div class="shop">
                 <div class="buy">
@@ -74,7 +87,8 @@ impl Component for Header {
                             html_nested!{
                             <Card<Turret>
                                 selected=is_turret_selected
-                                level=turret.level()
+                                level=turret.sprite()
+                                badge=(turret.sprite() != turret.level()).then(|| turret.level().to_string())
                                 price_text=turret.price_text()
                                 definition=128
                                 onclick=self.link.callback(|rc_t: Rc<Turret>| Msg::TurretSelected((*rc_t).clone()))
@@ -83,6 +97,9 @@ impl Component for Header {
                             />
                         }}) }
                     </div>
+                    <button class=classes!(upgrade_classes) title="Upgrade a turret" onclick=self.link.callback(|_| Msg::UpgradeMode)>
+                        { "⏫" }
+                    </button>
                 </div>
                 <div class="data">
                     <div class="sold">
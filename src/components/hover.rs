@@ -1,6 +1,8 @@
 use yew::prelude::*;
 
-use crate::game::GameStats;
+use milito_core::game::{score::Run, GameStats};
+
+use super::runs_table;
 
 pub struct Hover {
     link: ComponentLink<Self>,
@@ -10,13 +12,26 @@ pub struct Hover {
 #[derive(Properties, PartialEq, Clone)]
 pub struct HoverProps {
     pub game_stats: GameStats,
+    pub replay_link: Option<String>,
     pub make_pause: Callback<()>,
     pub more_wave: Callback<u32>,
+    /// The campaign levels end with their victory
+    pub can_play_more: bool,
+    pub select_level: Callback<()>,
+    /// The score of an endless run, with the leaderboard or the daily results, and the
+    /// index of the run in them
+    pub score: Option<u32>,
+    pub runs: Vec<Run>,
+    pub rank: Option<usize>,
+    pub can_resume: bool,
+    pub resume: Callback<bool>,
 }
 
 pub enum Msg {
     Unpause,
     MoreWave(u32),
+    SelectLevel,
+    Resume(bool),
 }
 
 impl Component for Hover {
@@ -46,10 +61,31 @@ impl Component for Hover {
                 self.props.more_wave.emit(amount);
                 true
             }
+            Msg::SelectLevel => {
+                self.props.select_level.emit(());
+                false
+            }
+            Msg::Resume(resume) => {
+                self.props.resume.emit(resume);
+                false
+            }
         }
     }
 
     fn view(&self) -> Html {
+        if self.props.can_resume {
+            return html! {
+                <div class="hover">
+                    <div class="game-stats">
+                        <div class="stats">{ "Welcome back" }</div>
+                        <p>{ "A game in progress has been saved at the end of its last wave." }</p>
+                        <button class="more-wave" onclick=self.link.callback(|_| Msg::Resume(true))>{ "Continue" }</button>
+                        <button class="more-wave" onclick=self.link.callback(|_| Msg::Resume(false))>{ "New game" }</button>
+                    </div>
+                </div>
+            };
+        }
+
         match &self.props.game_stats {
             GameStats::Playing => html! {},
             GameStats::Pause(_) => html! {
@@ -66,7 +102,10 @@ impl Component for Hover {
                             <li><kbd>{"G"}</kbd> {" to call god"}</li>
                             <li><kbd>{"U"}</kbd> {" to upgrade the player"}</li>
                             <li><kbd>{"D"}</kbd> {" to delete a turret"}</li>
-                            <li> <kbd title="The keys above your letters, not the numerical keypad" style="cursor: help;">{"1..6"}</kbd> {" to buy a new turret"}</li>
+                            <li><kbd>{"A"}</kbd> {" to upgrade a turret"}</li>
+                            <li><kbd>{"R"}</kbd> {" to repair a turret"}</li>
+                            <li><kbd>{"F"}</kbd> {" to show or hide the damage numbers"}</li>
+                            <li> <kbd title="The keys above your letters, not the numerical keypad" style="cursor: help;">{"1..0"}</kbd> {" to buy a new turret, with "} <kbd>{"Shift"}</kbd> {" for the next ten"}</li>
                             <li><kbd>{"Esc"}</kbd> {" or "} <kbd>{"Right click"}</kbd> {" to abort the current action"}</li>
                         </ul>
                         <p>{"Made with ♥ by "} <a href="https://github.com/tatounee/milito" target="_blank" >{"Tatoune"}</a> {"."}</p>
@@ -79,6 +118,13 @@ impl Component for Hover {
                         <div class="stats">{if matches!(x, GameStats::Victory) { "Victory"} else { "Defeat" }}</div>
                         <p>
                         {"If you want to replay, press " } <kbd> { "F5" } </kbd> { " or reload the page." } <br/>
+                        { if let Some(link) = &self.props.replay_link {
+                            html_nested! { <>
+                                { "You can share this run with " } <a href=link.clone() target="_blank">{ "this replay" }</a> { "." } <br/>
+                                </>}
+                        } else {
+                            html_nested! {}
+                        }}
                         { if matches!(x, GameStats::Victory) {
                             html_nested! { <> <br/>
                                 { "PS: I will tell you a secret, you can active a cheat mode by typing \"ilovetatoune\" and then, you can skip a wave if you press " } <kbd> { "S" } </kbd> { " when no wave is running." }
@@ -87,12 +133,21 @@ impl Component for Hover {
                             html_nested! {}
                         }}
                         </p>
-                        { if matches!(x, GameStats::Victory) {
+                        { if let Some(score) = self.props.score {
+                            html_nested! { <>
+                                <p>{ format!("Your score is {}.", score) }</p>
+                                { runs_table(&self.props.runs, self.props.rank) }
+                                </>}
+                        } else {
+                            html_nested! {}
+                        }}
+                        { if matches!(x, GameStats::Victory) && self.props.can_play_more {
                             html_nested! {
                                 <button class="more-wave" onclick=self.link.callback(|_| Msg::MoreWave(1))>{"I want to play one more wave !"}</button> }
                         } else {
                             html_nested! {}
                         }}
+                        <button class="more-wave" onclick=self.link.callback(|_| Msg::SelectLevel)>{ "Levels" }</button>
                     </div>
                 </div>
             },
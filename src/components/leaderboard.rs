@@ -0,0 +1,34 @@
+use yew::prelude::*;
+
+use milito_core::game::score::Run;
+
+/// The table of the runs, the one at the index `highlight` is the one just played.
+pub fn runs_table(runs: &[Run], highlight: Option<usize>) -> Html {
+    if runs.is_empty() {
+        return html! { <p>{ "No run yet, be the first one!" }</p> };
+    }
+
+    html! {
+        <table class="leaderboard">
+            <tr>
+                <th>{ "#" }</th>
+                <th>{ "Score" }</th>
+                <th>{ "Wave" }</th>
+                <th>{ "Date" }</th>
+                <th>{ "Seed" }</th>
+            </tr>
+            { for runs.iter().enumerate().map(|(idx, run)| {
+                let classes = if highlight == Some(idx) { "highlight" } else { "" };
+                html_nested! {
+                    <tr class=classes>
+                        <td>{ idx + 1 }</td>
+                        <td>{ run.score }</td>
+                        <td>{ run.wave }</td>
+                        <td>{ &run.date }</td>
+                        <td>{ run.seed }</td>
+                    </tr>
+                }
+            }) }
+        </table>
+    }
+}
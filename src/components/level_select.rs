@@ -0,0 +1,87 @@
+use yew::prelude::*;
+
+use milito_core::game::score::{DailyHistory, Leaderboard, Run};
+
+use super::runs_table;
+use crate::mode::Mode;
+
+/// Number of daily results shown.
+const DAILY_SHOWN: usize = 7;
+
+pub struct LevelSelect {
+    link: ComponentLink<Self>,
+    props: LevelSelectProps,
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct LevelSelectProps {
+    /// The name of each level of the campaign
+    pub levels: Vec<String>,
+    pub completed: Vec<bool>,
+    pub leaderboard: Leaderboard,
+    pub daily: DailyHistory,
+    /// The result of the daily challenge of today, if it has been played
+    pub today: Option<Run>,
+    pub select: Callback<Mode>,
+}
+
+pub enum Msg {
+    Select(Mode),
+}
+
+impl Component for LevelSelect {
+    type Message = Msg;
+    type Properties = LevelSelectProps;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self { link, props }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props != props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Select(mode) => self.props.select.emit(mode),
+        }
+        false
+    }
+
+    fn view(&self) -> Html {
+        let completed = |idx: usize| self.props.completed.get(idx).copied().unwrap_or_default();
+
+        html! {
+            <div class="hover">
+                <div class="level-select">
+                    <div class="stats">{ "Campaign" }</div>
+                    { for self.props.levels.iter().enumerate().map(|(idx, name)| {
+                        // A level is locked until the previous one is won
+                        let locked = idx > 0 && !completed(idx - 1);
+                        let text = format!("{}. {}{}", idx + 1, name, if completed(idx) { " ✓" } else { "" });
+                        html_nested! {
+                            <button class="more-wave level" disabled=locked onclick=self.link.callback(move |_| Msg::Select(Mode::Campaign(idx)))>
+                                { text }
+                            </button>
+                        }
+                    }) }
+                    <button class="more-wave level" onclick=self.link.callback(|_| Msg::Select(Mode::FreePlay))>{ "Free play" }</button>
+                    <button class="more-wave level" onclick=self.link.callback(|_| Msg::Select(Mode::Endless))>{ "Endless" }</button>
+                    { runs_table(self.props.leaderboard.runs(), None) }
+                    <button class="more-wave level" disabled=self.props.today.is_some() onclick=self.link.callback(|_| Msg::Select(Mode::Daily))>
+                        { match &self.props.today {
+                            Some(run) => format!("Daily challenge: {} points", run.score),
+                            None => "Daily challenge".to_owned(),
+                        } }
+                    </button>
+                    { runs_table(&self.props.daily.runs()[..self.props.daily.runs().len().min(DAILY_SHOWN)], None) }
+                </div>
+            </div>
+        }
+    }
+}
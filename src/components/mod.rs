@@ -1,12 +1,20 @@
 mod board;
+mod boss_bar;
 mod card;
 mod footer;
 mod game_row;
 mod header;
 mod hover;
+mod leaderboard;
+mod level_select;
 
-pub use board::{Board, BoardProps};
+pub use board::Board;
+pub use boss_bar::boss_bar;
 pub use footer::{Footer, FooterProps};
-pub use game_row::{GameRow, GameRowProps};
+pub use game_row::{
+    status_class, FloatingDamage, GameRow, GameRowProps, BEAM_LIFETIME, DAMAGE_LIFETIME,
+};
 pub use header::{Header, HeaderProps};
 pub use hover::{Hover, HoverProps};
+pub use leaderboard::runs_table;
+pub use level_select::{LevelSelect, LevelSelectProps};
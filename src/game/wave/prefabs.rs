@@ -1,196 +0,0 @@
-use lazy_static::lazy_static;
-
-use super::Wave;
-use crate::wave;
-
-lazy_static! {
-    static ref WAVE_1: Wave = wave![
-        0 => [1],
-        12 => [1],
-        25 => [1],
-        30 => [1],
-    ];
-    static ref WAVE_2: Wave = wave![
-        0 => [1, 1],
-        15 => [1],
-        30 => [1],
-        47 => [1, 1],
-    ];
-    static ref WAVE_3: Wave = wave![
-        0 => [2],
-        15 => [1],
-        25 => [1],
-        38 => [1, 1],
-        50 => [1],
-        55 => [1],
-        56 => [2, 2],
-        60 => [1, 1, 1, 1],
-        63 => [2, 2],
-    ];
-    static ref WAVE_4: Wave = wave![
-        0 => [1, 1],
-        5 => [1],
-        15 => [3],
-        20 => [1, 1],
-        30 => [2, 1],
-        34 => [1],
-        38 => [2, 3],
-    ];
-    static ref WAVE_5: Wave = wave![
-        0 => [3, 3],
-        5 => [1, 1, 1],
-        15 => [2],
-        20 => [1, 1],
-        30 => [3, 1],
-        40 => [2],
-        50 => [1, 1, 1, 1, 1],
-    ];
-    static ref WAVE_6: Wave = wave![
-        0 => [2, 1],
-        8 => [2, 2],
-        10 => [2, 2],
-        16 => [3, 3],
-        19 => [3],
-        20 => [3],
-        22 => [1],
-        25 => [3, 3],
-        28 => [2, 1],
-        30 => [1, 2, 2],
-        35 => [1, 1, 2],
-        45 => [1, 1, 2],
-        60 => [4],
-        65 => [1],
-        70 => [2, 2, 3],
-        80 => [1, 1, 1, 1],
-        90 => [3, 3, 1]
-    ];
-    static ref WAVE_7: Wave = wave![
-        0 => [3, 3, 3],
-        5 => [1, 1],
-        10 => [2, 2, 2, 1],
-        13 => [3, 3, 3, 3],
-        15 => [1, 1, 1, 2, 2],
-        20 => [3],
-        23 => [2, 1],
-        28 => [1, 1, 3, 2],
-        35 => [2, 2, 2],
-        40 => [2, 2],
-        50 => [1, 1, 2],
-        60 => [4, 1],
-        70 => [3, 3, 3, 1],
-        75 => [2, 3],
-        85 => [1, 1, 2, 3],
-        90 => [1, 1, 2, 3],
-    ];
-    static ref WAVE_8: Wave = wave![
-        0 => [2, 2, 1],
-        5 => [2, 2],
-        10 => [1],
-        15 => [1],
-        20 => [3, 3, 3],
-        25 => [4],
-        35 => [1, 1],
-        45 => [2, 2, 1, 1],
-        50 => [2, 2, 3],
-        60 => [1, 1, 1],
-        70 => [4, 1],
-        80 => [1, 1, 2],
-        85 => [2, 3],
-        95 => [4, 1, 1, 3],
-        100 => [1, 1, 1],
-        107 => [1],
-        115 => [2, 2, 2, 2, 2],
-    ];
-    static ref WAVE_9: Wave = wave![
-        0 => [2, 2, 1, 1, 3],
-        5 => [3, 3],
-        10 => [1, 1],
-        20 => [4],
-        35 => [4],
-        45 => [1, 1, 1, 1],
-        48 => [1, 1, 1],
-        52 => [2, 1, 1, 1],
-        60 => [3, 2],
-        70 => [1, 1],
-        75 => [4, 1, 2],
-        80 => [2, 3, 1],
-        90 => [3, 1, 1, 3],
-        95 => [1, 1, 2, 2],
-        105 => [1, 2, 3, 3],
-        110 => [2, 2, 3, 3],
-        115 => [1, 1],
-        118 => [2, 2, 2],
-        120 => [4],
-        122 => [3, 3],
-        125 => [3, 3, 3, 3, 3],
-        128 => [4, 2, 2, 3, 1],
-        131 => [2, 2, 2, 3],
-    ];
-    static ref WAVE_10: Wave = wave![
-        0 => [5],
-        1 => [5],
-        2 => [5],
-        3 => [5],
-        4 => [5],
-        5 => [6],
-        6 => [5],
-        7 => [5],
-        8 => [6],
-        9 => [5],
-        11 => [3, 3, 3, 3],
-        12 => [7, 7, 7],
-        13 => [3, 3, 3, 7],
-        14 => [7, 7, 7, 7, 3],
-        16 => [3, 7, 7, 3],
-        17 => [7, 7, 7, 3],
-        19 => [7, 7, 7, 7, 3],
-        21 => [3, 3, 5],
-        22 => [7, 7, 3, 3],
-        24 => [7, 3, 7, 7],
-        26 => [7, 6, 6, 6, 3],
-        28 => [3, 3, 7, 7],
-        38 => [6, 6, 6, 6, 6],
-        40 => [3, 3, 5, 5, 5],
-        42 => [5, 5, 5, 6],
-        44 => [4],
-        50 => [2, 2],
-        60 => [2, 4, 4],
-        65 => [6, 6, 6],
-        68 => [7, 7, 7, 7, 7],
-        70 => [2, 3, 3, 6],
-        75 => [3, 3, 1],
-        77 => [3, 3, 1, 6],
-        79 => [3, 3, 3, 4],
-        81 => [2, 6, 1, 1],
-        85 => [2, 3, 4],
-        90 => [6, 6, 6, 6, 6],
-        95 => [3, 1, 1, 4],
-        100 => [3, 2, 2, 2],
-        110 => [2, 3, 3, 4],
-        120 => [2, 3, 2],
-        125 => [5, 5, 5, 5, 5],
-        130 => [1, 6, 5],
-        134 => [2, 2, 3, 3],
-        136 => [6, 6, 3],
-        139 => [6, 6, 5, 3, 4],
-        142 => [3, 3, 5, 5, 5],
-        143 => [7, 7, 7, 7],
-        144 => [6, 6, 3, 3],
-        147 => [6, 6, 1, 3, 7],
-        148 => [6, 6, 4, 4],
-        150 => [6, 6, 4, 5],
-
-    ];
-    pub static ref WAVES: Vec<Wave> = vec![
-        WAVE_1.clone(),
-        WAVE_2.clone(),
-        WAVE_3.clone(),
-        WAVE_4.clone(),
-        WAVE_5.clone(),
-        WAVE_6.clone(),
-        WAVE_7.clone(),
-        WAVE_8.clone(),
-        WAVE_9.clone(),
-        WAVE_10.clone(),
-    ];
-}
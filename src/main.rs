@@ -1,6 +1,9 @@
+// The html! macro of yew 0.18 expands some props into bare statements
+#![allow(clippy::unnecessary_operation)]
+
 mod cheat;
 mod components;
-mod game;
+mod mode;
 mod utils;
 
 use std::time::Duration;
@@ -10,21 +13,42 @@ use yew::{
     prelude::*,
     services::{
         keyboard::{KeyListenerHandle, KeyboardService},
+        storage::{Area, StorageService},
         IntervalService, Task,
     },
     utils::window,
 };
 
 use components::{Footer, FooterProps, Header, HeaderProps};
-use game::{turret::Turret, wave::WAVES, ActionOnBoard, Game};
+use js_sys::{Date, Math::random};
+use milito_core::{
+    game::{
+        event::Event,
+        level::CAMPAIGN,
+        replay::{InputLog, Replay},
+        score::{DailyHistory, Leaderboard, Run},
+        turret::Turret,
+        ActionOnBoard, Game, GameStats,
+    },
+    FPS,
+};
 
-use crate::{
-    components::{Board, GameRow, GameRowProps, Hover, HoverProps},
-    game::GameStats,
+use crate::components::{
+    boss_bar, status_class, Board, FloatingDamage, GameRow, GameRowProps, Hover, HoverProps,
+    LevelSelect, LevelSelectProps, BEAM_LIFETIME, DAMAGE_LIFETIME,
 };
+use crate::mode::Mode;
 
-const FPS: u64 = 30;
 const FRAME_TIME: u64 = 1000 / FPS;
+const SAVE_KEY: &str = "milito.save";
+/// The mode of the saved game
+const SAVE_MODE_KEY: &str = "milito.save.mode";
+/// The indices of the campaign levels won, separated by commas
+const CAMPAIGN_KEY: &str = "milito.campaign";
+/// The best endless runs
+const LEADERBOARD_KEY: &str = "milito.leaderboard";
+/// The results of the daily challenges
+const DAILY_KEY: &str = "milito.daily";
 
 enum Msg {
     KeyDown(KeyboardEvent),
@@ -36,6 +60,9 @@ enum Msg {
     NextWave,
     Pause(bool),
     MoreWave(u32),
+    Resume(bool),
+    ShowLevels,
+    Play(Mode),
     Tick,
 }
 
@@ -47,8 +74,25 @@ struct Model {
     ticker: Box<dyn Task>,
     input_handler: KeyListenerHandle,
     cheat: Cheat,
+    replay: Option<Replay>,
+    storage: Option<StorageService>,
+    /// The saved game and its mode
+    saved_game: Option<(Game, Mode)>,
+    mode: Mode,
+    selecting_level: bool,
+    completed: Vec<bool>,
+    leaderboard: Leaderboard,
+    daily: DailyHistory,
+    /// Whether the endless run is in the leaderboard, and at which rank
+    run_recorded: Option<Option<usize>>,
+    show_damages: bool,
+    /// The damage numbers floating on each line
+    damages: Vec<Vec<FloatingDamage>>,
+    /// The columns struck by a beam, with the number of frames since the strike
+    beams: Vec<(usize, u32)>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Properties, Clone)]
 struct ListProps {
     list_turrets: Vec<Turret>,
@@ -70,10 +114,79 @@ impl Component for Model {
         let input_handler =
             KeyboardService::register_key_down(&window(), link.callback(Msg::KeyDown));
 
-        let mut game = Game::default();
-        game.add_waves(WAVES.clone());
-        game.generate_waves(5);
-        game.assign_line_for_enemies();
+        // A shared run can be watched with an url ending by `#replay=<input log>`,
+        // followed by `&mode=<mode>` when it is not the free play
+        let hash = window().location().hash().ok().unwrap_or_default();
+        let (replay, mode) = match hash.strip_prefix("#replay=") {
+            Some(replay) => {
+                let (log, mode) = match replay.split_once("&mode=") {
+                    Some((log, mode)) => (log, mode.parse::<Mode>().unwrap_or(Mode::FreePlay)),
+                    None => (replay, Mode::FreePlay),
+                };
+                match log.parse::<InputLog>() {
+                    Ok(log) => (Some(log), mode),
+                    Err(err) => {
+                        log!(err.to_string());
+                        (None, Mode::FreePlay)
+                    }
+                }
+            }
+            None => (None, Mode::FreePlay),
+        };
+
+        let game = mode.new_game(
+            replay
+                .as_ref()
+                .map(|log| log.seed())
+                .unwrap_or_else(random_seed),
+        );
+
+        let storage = StorageService::new(Area::Local).ok();
+
+        let saved_game = match storage.as_ref() {
+            Some(storage) if replay.is_none() => {
+                let save: Result<String, _> = storage.restore(SAVE_KEY);
+                let mode: Result<String, _> = storage.restore(SAVE_MODE_KEY);
+                let mode = mode
+                    .ok()
+                    .and_then(|mode| mode.parse::<Mode>().ok())
+                    .unwrap_or(Mode::FreePlay);
+                save.ok().and_then(|save| match Game::load(&save) {
+                    // The daily challenge cannot be resumed
+                    Ok(_) if mode == Mode::Daily => None,
+                    Ok(game) => Some((game, mode)),
+                    Err(err) => {
+                        log!(err.to_string());
+                        None
+                    }
+                })
+            }
+            _ => None,
+        };
+
+        let mut completed = vec![false; CAMPAIGN.len()];
+        let mut leaderboard = Leaderboard::default();
+        let mut daily = DailyHistory::default();
+        if let Some(storage) = storage.as_ref() {
+            let won: Result<String, _> = storage.restore(CAMPAIGN_KEY);
+            for idx in won.unwrap_or_default().split(',') {
+                if let Some(done) = idx
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|idx| completed.get_mut(idx))
+                {
+                    *done = true
+                }
+            }
+            let runs: Result<String, _> = storage.restore(LEADERBOARD_KEY);
+            if let Ok(runs) = runs {
+                leaderboard = Leaderboard::from_json(&runs);
+            }
+            let results: Result<String, _> = storage.restore(DAILY_KEY);
+            if let Ok(results) = results {
+                daily = DailyHistory::from_json(&results);
+            }
+        }
 
         let cheat = Cheat::new(if cfg!(debug_assertions) {
             ""
@@ -88,24 +201,54 @@ impl Component for Model {
             ticker: Box::new(ticker),
             input_handler,
             cheat,
+            selecting_level: replay.is_none() && saved_game.is_none(),
+            replay: replay.map(Replay::new),
+            storage,
+            saved_game,
+            mode,
+            completed,
+            leaderboard,
+            daily,
+            run_recorded: None,
+            show_damages: true,
+            damages: Vec::new(),
+            beams: Vec::new(),
         }
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
+            Msg::Tick if self.saved_game.is_some() || self.selecting_level => false,
             Msg::Tick => {
-                self.game.process();
+                if let Some(replay) = self.replay.as_mut() {
+                    replay.tick(&mut self.game)
+                } else {
+                    let was_playing = self.game.stats == GameStats::Playing;
+                    self.game.process();
+                    self.autosave(was_playing);
+                    self.mark_completed();
+                    self.record_run();
+                    self.update_daily();
+                }
+                self.update_damages();
+                self.update_beams();
                 true
             }
+            Msg::KeyDown(_) if self.saved_game.is_some() || self.selecting_level => false,
             Msg::KeyDown(event) => {
                 let key = event.key();
-                if !self.game.is_paused() {
+                if self.replay.is_some() {
+                    if key.as_str() == "Escape" {
+                        self.link.send_message(Msg::Pause(!self.game.is_paused()))
+                    }
+                } else if !self.game.is_paused() {
                     let code = event.code();
                     if code.len() == 6 && &code[0..5] == "Digit" {
                         event.prevent_default();
-                        if let Ok(nbr) = code[5..6].parse::<usize>() {
-                            if let Some(turret) = self.game.turret_list().get(nbr.saturating_sub(1))
-                            {
+                        if let Ok(digit) = code[5..6].parse::<usize>() {
+                            // 0 comes after 9, and Shift gives the next ten turrets
+                            let index = (digit + 9) % 10 + if event.shift_key() { 10 } else { 0 };
+                            if let Some(turret) = self.game.turret_list().get(index) {
                                 self.link
                                     .send_message(Msg::NewAction(ActionOnBoard::PlaceTurret(
                                         turret.as_ref().clone(),
@@ -125,6 +268,16 @@ impl Component for Model {
                         "d" => self
                             .link
                             .send_message(Msg::NewAction(ActionOnBoard::Delete)),
+                        "a" => self
+                            .link
+                            .send_message(Msg::NewAction(ActionOnBoard::Upgrade)),
+                        "r" => self
+                            .link
+                            .send_message(Msg::NewAction(ActionOnBoard::Repair)),
+                        "f" => {
+                            self.show_damages = !self.show_damages;
+                            self.damages.clear();
+                        }
                         " " => self.link.send_message(Msg::NextWave),
                         "u" => self.link.send_message(Msg::UpgradePlayer),
                         "Escape" => {
@@ -150,10 +303,16 @@ impl Component for Model {
 
                 false
             }
+            Msg::KillAll | Msg::UpgradePlayer | Msg::NextWave | Msg::MoreWave(_)
+                if self.replay.is_some() =>
+            {
+                false
+            }
             Msg::KillAll => {
                 self.game.use_god();
                 false
             }
+            Msg::ExectuteAction(_, _) if self.replay.is_some() => false,
             Msg::ExectuteAction(x, y) => {
                 if self.game.execute_action(x, y) {
                     self.show_grid = false;
@@ -194,15 +353,59 @@ impl Component for Model {
                 false
             }
             Msg::MoreWave(amount) => {
-                self.game.generate_waves(amount);
-                self.game.assign_line_for_enemies();
-                self.game.stats = GameStats::Playing;
+                self.game.more_waves(amount);
                 false
             }
             Msg::NextWave => {
                 self.game.start_next_wave();
                 false
             }
+            Msg::Resume(resume) => {
+                match self.saved_game.take() {
+                    Some((game, mode)) if resume => {
+                        self.game = game;
+                        self.mode = mode;
+                    }
+                    _ => {
+                        if let Some(storage) = self.storage.as_mut() {
+                            storage.remove(SAVE_KEY);
+                            storage.remove(SAVE_MODE_KEY)
+                        }
+                        self.selecting_level = true;
+                    }
+                }
+                true
+            }
+            Msg::ShowLevels => {
+                if self.replay.is_some() {
+                    // Leave the replay
+                    window().location().set_hash("").ok();
+                    self.replay = None;
+                }
+                self.selecting_level = true;
+                true
+            }
+            Msg::Play(mode) => {
+                self.game = if mode == Mode::Daily {
+                    // The attempt is used as soon as the challenge starts
+                    let today = today();
+                    let game = Game::daily(&today);
+                    if !self.daily.start(&today, game.seed()) {
+                        return false;
+                    }
+                    self.store_daily();
+                    game
+                } else {
+                    mode.new_game(random_seed())
+                };
+                self.mode = mode;
+                self.run_recorded = None;
+                self.selecting_level = false;
+                self.show_grid = false;
+                self.damages.clear();
+                self.beams.clear();
+                true
+            }
         }
     }
 
@@ -211,10 +414,39 @@ impl Component for Model {
     }
 
     fn view(&self) -> Html {
+        let replay_link = match self.game.stats {
+            GameStats::Victory | GameStats::Defeat if self.replay.is_none() => {
+                Some(match self.mode {
+                    Mode::FreePlay => format!("#replay={}", self.game.input_log()),
+                    mode => format!("#replay={}&mode={}", self.game.input_log(), mode),
+                })
+            }
+            _ => None,
+        };
+
         let hover_props = HoverProps {
             game_stats: self.game.stats.clone(),
+            replay_link,
             make_pause: self.link.callback(|_| Msg::Pause(false)),
             more_wave: self.link.callback(Msg::MoreWave),
+            can_play_more: self.mode == Mode::FreePlay,
+            select_level: self.link.callback(|_| Msg::ShowLevels),
+            score: self.endless_score(),
+            runs: match self.mode {
+                Mode::Endless => self.leaderboard.runs().to_vec(),
+                Mode::Daily => self.daily.runs().to_vec(),
+                _ => Vec::new(),
+            },
+            rank: match self.mode {
+                Mode::Daily => self
+                    .daily
+                    .runs()
+                    .iter()
+                    .position(|run| run.seed == self.game.seed()),
+                _ => self.run_recorded.flatten(),
+            },
+            can_resume: self.saved_game.is_some(),
+            resume: self.link.callback(Msg::Resume),
         };
 
         let header_props = HeaderProps {
@@ -224,13 +456,16 @@ impl Component for Model {
                 .game
                 .action
                 .as_ref()
-                .map(|act| act.get_turret_level())
-                .flatten(),
+                .and_then(|act| act.get_turret_level()),
             player_level: self.game.player.level,
             upgrade_cost_text: self.game.player.upgrade_cost_text(),
+            upgrade_mode: self.game.is_upgrade_mode(),
             on_turret_selected: self
                 .link
                 .callback(|turret: Turret| Msg::NewAction(ActionOnBoard::PlaceTurret(turret))),
+            toggle_upgrade_mode: self
+                .link
+                .callback(|_| Msg::NewAction(ActionOnBoard::Upgrade)),
             upgrade_player: self.link.callback(|_| Msg::UpgradePlayer),
             make_pause: self.link.callback(|_| Msg::Pause(true)),
         };
@@ -238,22 +473,50 @@ impl Component for Model {
         let footer_props = FooterProps {
             god_level: self.game.god_level(),
             wave: self.game.wave(),
+            score: self.endless_score(),
             delete_mode: self.game.is_delete_mode(),
+            repair_mode: self.game.is_repair_mode(),
             active_god: self.link.callback(|_| Msg::KillAll),
             toggle_delete_mode: self
                 .link
                 .callback(|_| Msg::NewAction(ActionOnBoard::Delete)),
+            toggle_repair_mode: self
+                .link
+                .callback(|_| Msg::NewAction(ActionOnBoard::Repair)),
             start_next_wave: self.link.callback(|_| Msg::NextWave),
             wave_ended: self.game.is_wave_ended(),
         };
 
         html! {
             <body oncontextmenu=self.link.callback(|e| Msg::AbortAction(Some(e)))>
-                <Hover with hover_props/>
+                { if self.selecting_level {
+                    let level_select_props = LevelSelectProps {
+                        levels: CAMPAIGN.iter().map(|level| level.name.clone()).collect(),
+                        completed: self.completed.clone(),
+                        leaderboard: self.leaderboard.clone(),
+                        daily: self.daily.clone(),
+                        today: self.daily.get(&today()).cloned(),
+                        select: self.link.callback(Msg::Play),
+                    };
+                    html! { <LevelSelect with level_select_props/> }
+                } else {
+                    html! { <Hover with hover_props/> }
+                }}
                 <Header with header_props/>
+                { self.game.boss().map(|boss| boss_bar(&boss)).unwrap_or_default() }
                 <Board show_grid=self.show_grid>
                     { for self.game.lines.iter().enumerate().map(|(y, line)| {
-                        let cells = line.cells.iter().map(|opt| opt.as_ref().map(|turret| turret.level())).collect::<Vec<_>>();
+                        let cells = line.cells.iter().map(|opt| opt.as_ref().map(|turret| (turret.sprite(), turret.health(), status_class(turret.statuses())))).collect::<Vec<_>>();
+                        let selectable = (0..cells.len())
+                            .map(|x| {
+                                let cost = if self.game.is_repair_mode() {
+                                    self.game.repair_cost(x, y)
+                                } else {
+                                    self.game.upgrade_cost(x, y)
+                                };
+                                cost.is_some_and(|cost| cost <= self.game.money)
+                            })
+                            .collect::<Vec<_>>();
 
                         let player_level = if self.game.player.line == y {
                             Some(self.game.player.level)
@@ -264,14 +527,22 @@ impl Component for Model {
                         let execute_action = self.link.callback(|(x, y)| Msg::ExectuteAction(x, y));
 
                         let game_row_props = GameRowProps {
+                            cell_size: self.game.board().cell_size(),
                             player_level,
                             cells,
+                            terrain: line.terrain(),
+                            cells_terrain: line.cells_terrain().to_vec(),
+                            selectable,
                             y,
                             execute_action,
                             show_grid: self.show_grid,
                             delete_mode: self.game.is_delete_mode(),
+                            upgrade_mode: self.game.is_upgrade_mode(),
+                            repair_mode: self.game.is_repair_mode(),
                             enemies: line.enemies.clone(),
                             projectiles: line.projectiles.clone(),
+                            damages: self.damages.get(y).cloned().unwrap_or_default(),
+                            beams: self.beams.clone(),
                         };
 
                         html_nested!( <GameRow with game_row_props/> )
@@ -284,6 +555,174 @@ impl Component for Model {
     }
 }
 
+impl Model {
+    /// Age the beams of the column lasers and add the ones of the last frame.
+    fn update_beams(&mut self) {
+        if self.game.stats != GameStats::Playing {
+            return;
+        }
+
+        self.beams.iter_mut().for_each(|(_, age)| *age += 1);
+        self.beams.retain(|(_, age)| *age < BEAM_LIFETIME);
+        for event in self.game.events() {
+            if let Event::BeamFired { x, .. } = *event {
+                self.beams.push((x, 0))
+            }
+        }
+    }
+
+    /// Age the floating damage numbers and add the ones of the last frame.
+    fn update_damages(&mut self) {
+        if !self.show_damages || self.game.stats != GameStats::Playing {
+            return;
+        }
+
+        self.damages.resize(self.game.lines.len(), Vec::new());
+        for damages in self.damages.iter_mut() {
+            damages.iter_mut().for_each(|damage| damage.age += 1);
+            damages.retain(|damage| damage.age < DAMAGE_LIFETIME);
+        }
+        for event in self.game.events() {
+            if let Event::ProjectileHit {
+                line, damage, x, ..
+            }
+            | Event::Burned { line, damage, x } = *event
+            {
+                self.damages[line].push(FloatingDamage {
+                    x,
+                    amount: damage,
+                    money: false,
+                    age: 0,
+                })
+            } else if let Event::MoneyProduced { line, x, money } = *event {
+                self.damages[line].push(FloatingDamage {
+                    x: self.game.board().turret_x(x),
+                    amount: money,
+                    money: true,
+                    age: 0,
+                })
+            }
+        }
+    }
+
+    /// Save the game each time a wave is cleared, and forget it once the game is over, which
+    /// `was_playing` before the last frame tells. The daily challenge is never saved, its attempt
+    /// is used once started.
+    fn autosave(&mut self, was_playing: bool) {
+        let wave_cleared = self
+            .game
+            .events()
+            .iter()
+            .any(|event| matches!(event, Event::WaveCleared { .. }));
+        if let Some(storage) = self.storage.as_mut() {
+            match self.game.stats {
+                GameStats::Victory | GameStats::Defeat if was_playing => {
+                    storage.remove(SAVE_KEY);
+                    storage.remove(SAVE_MODE_KEY)
+                }
+                GameStats::Playing if wave_cleared && self.mode != Mode::Daily => {
+                    storage.store(SAVE_KEY, Ok(self.game.save()));
+                    storage.store(SAVE_MODE_KEY, Ok(self.mode.to_string()));
+                }
+                _ => (),
+            }
+        }
+    }
+
+    #[inline]
+    fn endless_score(&self) -> Option<u32> {
+        if matches!(self.mode, Mode::Endless | Mode::Daily) {
+            Some(self.game.score())
+        } else {
+            None
+        }
+    }
+
+    /// Add the endless run to the leaderboard once it is lost.
+    fn record_run(&mut self) {
+        if self.mode != Mode::Endless
+            || self.game.stats != GameStats::Defeat
+            || self.run_recorded.is_some()
+        {
+            return;
+        }
+        let rank = self.leaderboard.insert(Run {
+            score: self.game.score(),
+            wave: self.game.wave(),
+            seed: self.game.seed(),
+            date: today(),
+        });
+        self.run_recorded = Some(rank);
+        if let (Some(storage), Some(_)) = (self.storage.as_mut(), rank) {
+            storage.store(LEADERBOARD_KEY, Ok(self.leaderboard.to_json()))
+        }
+    }
+
+    /// Keep the result of the daily challenge up to date, at the end of each wave and of the game.
+    fn update_daily(&mut self) {
+        let wave_cleared = self
+            .game
+            .events()
+            .iter()
+            .any(|event| matches!(event, Event::WaveCleared { .. }));
+        let lost = self.game.stats == GameStats::Defeat && self.run_recorded.is_none();
+        if self.mode != Mode::Daily || !(wave_cleared || lost) {
+            return;
+        }
+        if lost {
+            self.run_recorded = Some(None);
+        }
+        self.daily
+            .update(self.game.seed(), self.game.score(), self.game.wave());
+        self.store_daily();
+    }
+
+    #[inline]
+    fn store_daily(&mut self) {
+        if let Some(storage) = self.storage.as_mut() {
+            storage.store(DAILY_KEY, Ok(self.daily.to_json()))
+        }
+    }
+
+    /// Remember the campaign level once it is won, which unlocks the next one.
+    fn mark_completed(&mut self) {
+        let level = match self.mode {
+            Mode::Campaign(level) if self.game.stats == GameStats::Victory => level,
+            _ => return,
+        };
+        if self.completed.get(level) != Some(&false) {
+            return;
+        }
+        self.completed[level] = true;
+        let won = self
+            .completed
+            .iter()
+            .enumerate()
+            .filter(|(_, done)| **done)
+            .map(|(idx, _)| idx.to_string())
+            .collect::<Vec<_>>();
+        if let Some(storage) = self.storage.as_mut() {
+            storage.store(CAMPAIGN_KEY, Ok(won.join(",")))
+        }
+    }
+}
+
+#[inline]
+fn random_seed() -> u64 {
+    (random() * u64::MAX as f64) as u64
+}
+
+/// The UTC date, as `YYYY-MM-DD`, the same for every player.
+fn today() -> String {
+    let date = Date::new_0();
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.get_utc_full_year(),
+        date.get_utc_month() + 1,
+        date.get_utc_date()
+    )
+}
+
 fn main() {
     yew::initialize();
     yew::App::<Model>::new().mount_as_body();
@@ -0,0 +1,51 @@
+use std::{fmt, str::FromStr};
+
+use milito_core::game::{level::CAMPAIGN, Game};
+
+/// What is played, it is kept with the saves and the replays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// The index of a level of the campaign
+    Campaign(usize),
+    FreePlay,
+    Endless,
+    /// The endless game of the day, with a single attempt
+    Daily,
+}
+
+impl Mode {
+    pub fn new_game(&self, seed: u64) -> Game {
+        match self {
+            Self::Campaign(level) => match CAMPAIGN.get(*level) {
+                Some(level) => Game::from_level(seed, level),
+                None => Game::standard(seed),
+            },
+            Self::FreePlay => Game::standard(seed),
+            Self::Endless | Self::Daily => Game::endless(seed),
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Campaign(level) => write!(f, "{}", level),
+            Self::FreePlay => write!(f, "free"),
+            Self::Endless => write!(f, "endless"),
+            Self::Daily => write!(f, "daily"),
+        }
+    }
+}
+
+impl FromStr for Mode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "free" => Ok(Self::FreePlay),
+            "endless" => Ok(Self::Endless),
+            "daily" => Ok(Self::Daily),
+            level => level.parse().map(Self::Campaign).map_err(|_| ()),
+        }
+    }
+}